@@ -9,22 +9,309 @@ rust
     const host = document.getElementById("application-container");
     dotAppModule.mount(host, app);
 
-    // Add default dot event handler
+    // Errors from rust carry a `kind`, like "unknown_id" or "patch"
+    const reportError = (kind, message) => console.error(`${kind}: ${message}`);
+    const rerender = () => {
+      try {
+        dotAppModule.rerender_app(host, app);
+      } catch (error) {
+        reportError(error.kind, error.message);
+      }
+    };
+
+    // Patching the page only changes attributes and text, which set the
+    // default values of form controls. Once a control has been edited it
+    // shows its own value instead, so controls are set back to their
+    // defaults when the state changes under them. The control being
+    // edited is left alone, so typing in it isn't interrupted.
+    const syncFormValues = (edited = null) => {
+      host.querySelectorAll("input, textarea, select").forEach(control => {
+        if (control === edited || control.type === "file") {
+          return;
+        }
+        if (control.tagName === "SELECT") {
+          Array.from(control.options).forEach(option => {
+            option.selected = option.defaultSelected;
+          });
+        } else if (control.type === "checkbox") {
+          control.checked = control.defaultChecked;
+        } else {
+          control.value = control.defaultValue;
+        }
+      });
+    };
+
+    // Every edit is sent to rust as an action, and the app is rendered
+    // again if it was applied. `edited` is the control the edit came
+    // from, if any.
+    const dispatch = (action, edited = null) => {
+      const result = JSON.parse(
+        dotAppModule.dispatch(app, JSON.stringify(action))
+      );
+      if (result.status === "rejected") {
+        reportError(result.kind, result.error);
+        return;
+      }
+      result.warnings.forEach(warning => console.warn(warning));
+      rerender();
+      syncFormValues(edited);
+    };
+
+    const readFile = (file, action) => {
+      if (file) {
+        file.text().then(source => dispatch(action(source)));
+      }
+    };
+
+    // Inputs for a ring carry its index, and inputs for the defaults
+    // carry none
+    const getRingIndex = target => {
+      const ring_index = target.getAttribute("data_ring_index");
+      return ring_index === null || ring_index === ""
+        ? null
+        : parseInt(ring_index, 10);
+    };
+
     document.addEventListener("input", e => {
       const target = e.target;
-      const dot_id = target.getAttribute("data_dot_id");
-      const input_name = target.getAttribute("name");
-      const new_value = parseFloat(target.value);
-      if (dot_id.startsWith("default")) {
-        const updated = dotAppModule.action_update_default_dot(
-          app,
-          dot_id,
-          input_name,
-          new_value
-        );
-        if (updated) {
-          dotAppModule.rerender_app(host, app);
+      const edit = action => dispatch(action, target);
+      const input_type = target.getAttribute("data_input_type");
+      const name = target.getAttribute("name");
+      const dot = target.getAttribute("data_dot_id");
+
+      switch (input_type) {
+        case "ring_text":
+          edit({ type: "set_text", text: target.value });
+          return;
+
+        case "expose_text":
+          edit({ type: "set_expose_text", expose_text: target.checked });
+          return;
+
+        case "background": {
+          const form = target.form.elements;
+          edit({
+            type: "set_background",
+            shape: {
+              type: form["background_shape"].value,
+              corner_radius: parseFloat(form["corner_radius"].value)
+            },
+            padding: {
+              value: parseFloat(form["padding"].value),
+              unit: form["padding_unit"].value
+            },
+            print_scale: parseFloat(form["print_scale"].value)
+          });
+          return;
+        }
+
+        case "open_design": {
+          const file = target.files[0];
+          const extension = file ? file.name.split(".").pop().toLowerCase() : "";
+          readFile(file, source =>
+            extension === "json" || extension === "toml"
+              ? { type: "load_document", source: source, format: extension }
+              : { type: "import_svg", source: source }
+          );
+          return;
+        }
+
+        case "center_image": {
+          const file = target.files[0];
+          if (!file) {
+            edit({ type: "set_center_image", data_url: null });
+            return;
+          }
+          const reader = new FileReader();
+          reader.onload = () =>
+            edit({ type: "set_center_image", data_url: reader.result });
+          reader.readAsDataURL(file);
+          return;
+        }
+
+        case "theme":
+          edit({ type: "apply_theme", theme: target.value });
+          return;
+
+        case "ring_line":
+          edit({
+            type: "set_ring_text",
+            ring_index: getRingIndex(target),
+            text: target.value
+          });
+          return;
+
+        case "ring_preset":
+          edit({
+            type: "set_ring_preset",
+            ring_index: getRingIndex(target),
+            preset: target.value || null
+          });
+          return;
+
+        case "ring_override":
+          edit({
+            type: target.checked ? "set_ring_override" : "clear_ring_override",
+            ring_index: getRingIndex(target),
+            part: name
+          });
+          return;
+
+        case "arc_input":
+          edit({
+            type: "set_arc_value",
+            ring_index: getRingIndex(target),
+            field: name,
+            value: parseFloat(target.value)
+          });
+          return;
+
+        case "color_vision":
+          edit({ type: "set_preview_vision", vision: target.value });
+          return;
+
+        case "style_color":
+          edit({ type: "set_style_color", field: name, color: target.value });
+          return;
+
+        case "color_mode": {
+          const form = target.form.elements;
+          edit({
+            type: "set_color_mode",
+            mode: form["color_mode"].value,
+            colors: form["color_mode_colors"].value
+          });
+          return;
         }
+
+        case "dot_shape":
+          edit({ type: "set_dot_shape", dot: dot, shape: target.value });
+          return;
+
+        case "dot_color":
+          edit({
+            type: "set_dot_color",
+            dot: dot,
+            field: name,
+            color: target.value || null
+          });
+          return;
+
+        case "dot_glyph":
+          readFile(target.files[0], source => ({
+            type: "import_dot_glyph",
+            dot: dot,
+            source: source
+          }));
+          return;
+
+        case "dot_input":
+          edit({
+            type: "set_dot_value",
+            dot: dot,
+            field: name,
+            value: parseFloat(target.value)
+          });
+          return;
+
+        // preset names are saved on change, once the name is complete
+      }
+    });
+
+    // Presets are saved when their name is committed, not per keystroke
+    document.addEventListener("change", e => {
+      const target = e.target;
+      // a slider was let go, so its next drag is undone on its own
+      dotAppModule.finish_edit(app);
+      if (target.getAttribute("data_input_type") === "preset_name") {
+        dispatch({ type: "save_preset", name: target.value }, target);
+      }
+    });
+
+    // Arc dials are dragged by their handles. Dragging the start moves
+    // where the arc begins and leaves its end in place, and dragging the
+    // end changes how far it sweeps.
+    let dialDrag = null;
+    // How far around the dial the pointer is, in turns from the right.
+    // The center is kept from the start of the drag, since rendering can
+    // replace the dial while it is dragged.
+    const getDialTurns = (center, e) => {
+      const x = e.clientX - center.x;
+      const y = e.clientY - center.y;
+      const turns = Math.atan2(y, x) / (2 * Math.PI);
+      return turns < 0 ? turns + 1 : turns;
+    };
+    const wrapTurns = turns => ((turns % 1) + 1) % 1;
+
+    document.addEventListener("pointerdown", e => {
+      const handle = e.target.closest("[data_dial_handle]");
+      if (!handle) {
+        return;
+      }
+      const dial = handle.closest(".arc-dial");
+      const offset = parseFloat(dial.getAttribute("data_arc_offset_percentage"));
+      const sweep = parseFloat(dial.getAttribute("data_arc_percentage"));
+      const rect = dial.getBoundingClientRect();
+      dialDrag = {
+        center: {
+          x: rect.left + rect.width / 2,
+          y: rect.top + rect.height / 2
+        },
+        handle: handle.getAttribute("data_dial_handle"),
+        ring_index: getRingIndex(dial),
+        offset: offset,
+        end: offset + sweep
+      };
+      e.preventDefault();
+    });
+
+    document.addEventListener("pointermove", e => {
+      if (!dialDrag) {
+        return;
+      }
+      const turns = getDialTurns(dialDrag.center, e);
+      if (dialDrag.handle === "start") {
+        dispatch({
+          type: "set_arc_dial",
+          ring_index: dialDrag.ring_index,
+          arc_offset_percentage: turns,
+          arc_percentage: wrapTurns(dialDrag.end - turns)
+        });
+      } else {
+        dispatch({
+          type: "set_arc_value",
+          ring_index: dialDrag.ring_index,
+          field: "arc_percentage",
+          value: wrapTurns(turns - dialDrag.offset)
+        });
+      }
+    });
+
+    // the whole drag undoes as one edit
+    document.addEventListener("pointerup", () => {
+      if (dialDrag) {
+        dialDrag = null;
+        dotAppModule.finish_edit(app);
+      }
+    });
+
+    // Ctrl+Z undoes and Ctrl+Shift+Z redoes, or Cmd on macs. Text
+    // fields keep their own undo.
+    document.addEventListener("keydown", e => {
+      if (!(e.ctrlKey || e.metaKey) || e.key.toLowerCase() !== "z") {
+        return;
+      }
+      const target = e.target;
+      if (target.matches("textarea, input[type=text]")) {
+        return;
+      }
+      e.preventDefault();
+      const changed = e.shiftKey
+        ? dotAppModule.redo(app)
+        : dotAppModule.undo(app);
+      if (changed) {
+        rerender();
+        syncFormValues();
       }
     });
   })
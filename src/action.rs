@@ -0,0 +1,809 @@
+use crate::color::{parse_color_list, Color, ColorVision};
+use crate::document::{Document, DocumentFormat, LoadedDocument};
+use crate::drawing_style::ColorMode;
+use crate::error::AppError;
+use crate::fig::background::{is_image_data_url, BackgroundShape};
+use crate::fig::diagram::Diagram;
+use crate::fig::dot::Dot;
+use crate::fig::shape::{
+    DotShape, Glyph, MAX_CROSS_ARM_WIDTH_RATIO, MAX_STAR_INNER_RADIUS_RATIO, MAX_STAR_POINTS,
+    MIN_STAR_POINTS,
+};
+use crate::fig::style_override::{ArcOverride, DotOverride};
+use crate::fig::text_path::{ArcStyle, TextPath};
+use crate::geom::Length;
+use crate::start::FullApplicationState;
+use crate::svg::embedded_document::{find_ring_labels, load_embedded_document};
+use crate::themes::get_theme;
+use crate::utf_to_binary::text_to_binary;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+fn get_dot_value_name(value: bool) -> &'static str {
+    return if value { "one" } else { "zero" };
+}
+
+/// A dot style the editor edits: one of the style's two default dots,
+/// or a ring's override for its one or zero dots. Named by the ids the
+/// editor gives their inputs, like "default-one-dot" or "ring-2-zero-dot".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DotId {
+    Default { value: bool },
+    Ring { ring_index: usize, value: bool },
+}
+
+impl DotId {
+    pub fn from_name(name: &str) -> Option<DotId> {
+        let parse_value = |value: &str| match value {
+            "zero" => Some(false),
+            "one" => Some(true),
+            _ => None,
+        };
+        let parts: Vec<&str> = name.split('-').collect();
+        return match parts.as_slice() {
+            ["default", value, "dot"] => Some(DotId::Default {
+                value: parse_value(value)?,
+            }),
+            ["ring", ring_index, value, "dot"] => Some(DotId::Ring {
+                ring_index: ring_index.parse().ok()?,
+                value: parse_value(value)?,
+            }),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> String {
+        return match self {
+            DotId::Default { value } => format!("default-{}-dot", get_dot_value_name(*value)),
+            DotId::Ring { ring_index, value } => {
+                format!("ring-{}-{}-dot", ring_index, get_dot_value_name(*value))
+            }
+        };
+    }
+}
+
+impl Serialize for DotId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&self.get_name());
+    }
+}
+
+impl<'de> Deserialize<'de> for DotId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DotId, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        return DotId::from_name(&name)
+            .ok_or_else(|| D::Error::custom(format!("{} is not a dot id", name)));
+    }
+}
+
+/// Name of the default arc, or of a ring's arc override
+fn get_arc_name(ring_index: Option<usize>) -> String {
+    return match ring_index {
+        Some(ring_index) => format!("ring-{}-arc", ring_index),
+        None => "default-arc".to_string(),
+    };
+}
+
+/// A number on a dot. The shape fields only apply to dots of that shape.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DotField {
+    CircleRadius,
+    RingRadius,
+    RingStrokeWidth,
+    Opacity,
+    StarPoints,
+    StarInnerRadiusRatio,
+    CrossArmWidthRatio,
+}
+
+impl DotField {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            DotField::CircleRadius => "circle_radius",
+            DotField::RingRadius => "ring_radius",
+            DotField::RingStrokeWidth => "ring_stroke_width",
+            DotField::Opacity => "opacity",
+            DotField::StarPoints => "star_points",
+            DotField::StarInnerRadiusRatio => "star_inner_radius_ratio",
+            DotField::CrossArmWidthRatio => "cross_arm_width_ratio",
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DotColorField {
+    FillColor,
+    RingColor,
+}
+
+impl DotColorField {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            DotColorField::FillColor => "fill_color",
+            DotColorField::RingColor => "ring_color",
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArcField {
+    Radius,
+    ArcPercentage,
+    ArcOffsetPercentage,
+}
+
+impl ArcField {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ArcField::Radius => "radius",
+            ArcField::ArcPercentage => "arc_percentage",
+            ArcField::ArcOffsetPercentage => "arc_offset_percentage",
+        };
+    }
+}
+
+/// The parts of a ring's style it can override as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RingStylePart {
+    ZeroDotStyle,
+    OneDotStyle,
+    ArcStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StyleColorField {
+    StrokeColor,
+    BackgroundColor,
+}
+
+impl StyleColorField {
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            StyleColorField::StrokeColor => "stroke_color",
+            StyleColorField::BackgroundColor => "background_color",
+        };
+    }
+}
+
+/// A change to the editor's state. Every edit the editor makes is one of
+/// these, sent from js as json.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Replaces the text of every ring, one ring per line, as typed into
+    /// the ring text area
+    SetText {
+        text: String,
+    },
+    SetRingText {
+        ring_index: usize,
+        text: String,
+    },
+    /// Adds a ring outside the others
+    AddRing {
+        text: String,
+    },
+    RemoveRing {
+        ring_index: usize,
+    },
+    SetDotValue {
+        dot: DotId,
+        field: DotField,
+        value: f64,
+    },
+    /// Sets the fill or ring color of a dot. No color clears it, so the
+    /// dot falls back to the style's stroke color.
+    SetDotColor {
+        dot: DotId,
+        field: DotColorField,
+        color: Option<Color>,
+    },
+    /// Switches a dot to one of the built in shapes, by name
+    SetDotShape {
+        dot: DotId,
+        shape: String,
+    },
+    /// Switches a dot to a custom glyph, from either raw svg path data or
+    /// the contents of an svg file
+    ImportDotGlyph {
+        dot: DotId,
+        source: String,
+    },
+    /// Sets a number on the default arc, or on a ring's arc override
+    SetArcValue {
+        #[serde(default)]
+        ring_index: Option<usize>,
+        field: ArcField,
+        value: f64,
+    },
+    /// Sets where an arc starts and how far it sweeps at once, as the arc
+    /// dial does when the start of the arc is dragged and its end stays put
+    SetArcDial {
+        #[serde(default)]
+        ring_index: Option<usize>,
+        arc_offset_percentage: f64,
+        arc_percentage: f64,
+    },
+    SetStyleColor {
+        field: StyleColorField,
+        color: Color,
+    },
+    /// Switches the style's color mode. `colors` is a comma separated
+    /// list of css colors: the palette for ring palettes, or the two ends
+    /// of a gradient.
+    SetColorMode {
+        mode: String,
+        colors: String,
+    },
+    /// Updates the diagram's background. Physical padding is converted
+    /// to diagram units with `print_scale`, in millimeters per unit.
+    SetBackground {
+        shape: BackgroundShape,
+        padding: Length,
+        print_scale: f64,
+    },
+    /// Sets the image shown in the middle of the diagram from a data
+    /// url, or removes it
+    SetCenterImage {
+        data_url: Option<String>,
+    },
+    /// Sets whether the diagram's text is exposed to assistive technology
+    SetExposeText {
+        expose_text: bool,
+    },
+    /// Previews the diagram in the editor as seen with a kind of color
+    /// blindness, or with normal color vision
+    SetPreviewVision {
+        vision: ColorVision,
+    },
+    /// Replaces the design with one loaded from a saved document
+    LoadDocument {
+        source: String,
+        format: DocumentFormat,
+    },
+    /// Opens an exported svg. The design embedded in it is used if there
+    /// is one, and otherwise only the text of each ring is read back from
+    /// its accessible label, keeping the current styles.
+    ImportSvg {
+        source: String,
+    },
+    /// Switches the style to a built in theme, keeping saved presets
+    ApplyTheme {
+        theme: String,
+    },
+    /// Saves the default dot and arc styles as a named preset
+    SavePreset {
+        name: String,
+    },
+    /// Makes a ring take its styles from a preset, or from the defaults
+    /// again
+    SetRingPreset {
+        ring_index: usize,
+        preset: Option<String>,
+    },
+    /// Gives a ring its own zero dot, one dot or arc style, starting from
+    /// the style it inherits
+    SetRingOverride {
+        ring_index: usize,
+        part: RingStylePart,
+    },
+    /// Makes a ring inherit its zero dot, one dot or arc style again
+    ClearRingOverride {
+        ring_index: usize,
+        part: RingStylePart,
+    },
+}
+
+fn check_text(text: &str) -> Result<(), AppError> {
+    if text_to_binary(text).is_none() {
+        return Err(AppError::invalid_value(
+            "text",
+            "has characters other than ascii, which can't be encoded",
+        ));
+    }
+    return Ok(());
+}
+
+fn get_ring(diagram: &Diagram, ring_index: usize) -> Result<&TextPath, AppError> {
+    return diagram
+        .paths
+        .get(ring_index)
+        .ok_or_else(|| AppError::unknown_id("ring", &ring_index.to_string()));
+}
+
+fn get_ring_mut(diagram: &mut Diagram, ring_index: usize) -> Result<&mut TextPath, AppError> {
+    return diagram
+        .paths
+        .get_mut(ring_index)
+        .ok_or_else(|| AppError::unknown_id("ring", &ring_index.to_string()));
+}
+
+/// Writes the fields that differ between `before` and `after` into an
+/// override, leaving the rest inherited
+fn update_dot_override(dot_override: &mut DotOverride, before: &Dot, after: &Dot) {
+    if after.shape != before.shape {
+        dot_override.shape = Some(after.shape.clone());
+    }
+    if after.circle_radius != before.circle_radius {
+        dot_override.circle_radius = Some(after.circle_radius);
+    }
+    if after.ring_radius != before.ring_radius {
+        dot_override.ring_radius = Some(after.ring_radius);
+    }
+    if after.ring_stroke_width != before.ring_stroke_width {
+        dot_override.ring_stroke_width = Some(after.ring_stroke_width);
+    }
+    if after.fill_color != before.fill_color {
+        dot_override.fill_color = after.fill_color;
+    }
+    if after.ring_color != before.ring_color {
+        dot_override.ring_color = after.ring_color;
+    }
+    if after.opacity != before.opacity {
+        dot_override.opacity = after.opacity;
+    }
+}
+
+/// Applies an edit to a dot style. Default dots are edited in place. A
+/// ring's dot is edited as it currently resolves, and only the fields
+/// the edit changed are written to the ring's override.
+fn edit_dot<F>(state: &mut FullApplicationState, dot: DotId, edit: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut Dot) -> Result<(), AppError>,
+{
+    return match dot {
+        DotId::Default { value: true } => edit(&mut state.style.default_one_dot_style),
+        DotId::Default { value: false } => edit(&mut state.style.default_zero_dot_style),
+        DotId::Ring { ring_index, value } => {
+            let before: Dot = get_ring(&state.diagram, ring_index)?
+                .resolve_dot_style(&state.style, value, None)
+                .to_dot();
+            let mut after: Dot = before.clone();
+            edit(&mut after)?;
+
+            let path: &mut TextPath = get_ring_mut(&mut state.diagram, ring_index)?;
+            let dot_override: &mut DotOverride = if value {
+                &mut path.one_dot_style
+            } else {
+                &mut path.zero_dot_style
+            };
+            update_dot_override(dot_override, &before, &after);
+            Ok(())
+        }
+    };
+}
+
+fn set_dot_value(dot: &mut Dot, field: DotField, value: f64) -> Result<(), AppError> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(AppError::invalid_value(
+            field.get_name(),
+            "must be a positive number",
+        ));
+    }
+    match (field, &mut dot.shape) {
+        (DotField::CircleRadius, _) => dot.circle_radius = value,
+        (DotField::RingRadius, _) => dot.ring_radius = value,
+        (DotField::RingStrokeWidth, _) => dot.ring_stroke_width = value,
+        (DotField::Opacity, _) if value <= 1.0 => dot.opacity = Some(value),
+        (DotField::Opacity, _) => {
+            return Err(AppError::invalid_value(
+                "opacity",
+                "must be between 0 and 1",
+            ));
+        }
+        (DotField::StarPoints, DotShape::Star { points, .. }) => {
+            *points = (value as u32).max(MIN_STAR_POINTS).min(MAX_STAR_POINTS);
+        }
+        (DotField::StarInnerRadiusRatio, DotShape::Star { .. })
+            if value > MAX_STAR_INNER_RADIUS_RATIO =>
+        {
+            return Err(AppError::invalid_value(
+                "star_inner_radius_ratio",
+                "must be between 0 and 1",
+            ));
+        }
+        (
+            DotField::StarInnerRadiusRatio,
+            DotShape::Star {
+                inner_radius_ratio, ..
+            },
+        ) => {
+            *inner_radius_ratio = value;
+        }
+        (DotField::CrossArmWidthRatio, DotShape::Cross { .. })
+            if value > MAX_CROSS_ARM_WIDTH_RATIO =>
+        {
+            return Err(AppError::invalid_value(
+                "cross_arm_width_ratio",
+                "must be between 0 and 2",
+            ));
+        }
+        (DotField::CrossArmWidthRatio, DotShape::Cross { arm_width_ratio }) => {
+            *arm_width_ratio = value;
+        }
+        _ => {
+            return Err(AppError::invalid_value(
+                field.get_name(),
+                "does not apply to the dot's shape",
+            ));
+        }
+    };
+    return Ok(());
+}
+
+fn set_arc_value(arc_style: &mut ArcStyle, field: ArcField, value: f64) -> Result<(), AppError> {
+    if !value.is_finite() {
+        return Err(AppError::invalid_value(
+            field.get_name(),
+            "must be a number",
+        ));
+    }
+    match field {
+        ArcField::Radius if value > 0.0 => arc_style.radius = value,
+        ArcField::Radius => return Err(AppError::invalid_value("radius", "must be positive")),
+        ArcField::ArcPercentage if value >= 0.0 && value <= 1.0 => arc_style.arc_percentage = value,
+        ArcField::ArcPercentage => {
+            return Err(AppError::invalid_value(
+                "arc_percentage",
+                "must be between 0 and 1",
+            ));
+        }
+        ArcField::ArcOffsetPercentage => arc_style.arc_offset_percentage = value,
+    };
+    return Ok(());
+}
+
+/// Writes the fields that differ between `before` and `after` to a ring's
+/// arc override, leaving the rest inherited
+fn update_arc_override(arc_override: &mut ArcOverride, before: &ArcStyle, after: &ArcStyle) {
+    if after.radius != before.radius {
+        arc_override.radius = Some(after.radius);
+    }
+    if after.arc_percentage != before.arc_percentage {
+        arc_override.arc_percentage = Some(after.arc_percentage);
+    }
+    if after.arc_offset_percentage != before.arc_offset_percentage {
+        arc_override.arc_offset_percentage = Some(after.arc_offset_percentage);
+    }
+}
+
+/// Applies an edit to the default arc, or to a ring's arc the same way
+/// `edit_dot` does for dots. Nothing is written if the edit fails.
+fn edit_arc<F>(
+    state: &mut FullApplicationState,
+    ring_index: Option<usize>,
+    edit: F,
+) -> Result<(), AppError>
+where
+    F: FnOnce(&mut ArcStyle) -> Result<(), AppError>,
+{
+    let ring_index: usize = match ring_index {
+        Some(ring_index) => ring_index,
+        None => {
+            let mut arc_style: ArcStyle = state.style.default_arc_style.clone();
+            edit(&mut arc_style)?;
+            state.style.default_arc_style = arc_style;
+            return Ok(());
+        }
+    };
+    let before: ArcStyle = get_ring(&state.diagram, ring_index)?
+        .resolve_arc_style(&state.style)
+        .to_arc_style();
+    let mut after: ArcStyle = before.clone();
+    edit(&mut after)?;
+
+    update_arc_override(
+        &mut get_ring_mut(&mut state.diagram, ring_index)?.arc_style,
+        &before,
+        &after,
+    );
+    return Ok(());
+}
+
+fn load_design(state: &mut FullApplicationState, loaded: LoadedDocument) -> Vec<String> {
+    state.style = loaded.document.style;
+    state.diagram = loaded.document.diagram;
+    return loaded.warnings;
+}
+
+impl Action {
+    /// Whether undo should revert the action. Preview settings belong to
+    /// the editor, not the design, so they aren't undone.
+    pub fn is_design_edit(&self) -> bool {
+        return match self {
+            Action::SetPreviewVision { .. } => false,
+            _ => true,
+        };
+    }
+
+    /// Identifies the input an action comes from, for actions that are
+    /// sent continuously while a slider is dragged or text is typed.
+    /// Undo merges actions with the same key into one step.
+    pub fn get_edit_key(&self) -> Option<String> {
+        return match self {
+            Action::SetText { .. } => Some("text".to_string()),
+            Action::SetRingText { ring_index, .. } => Some(format!("ring_text/{}", ring_index)),
+            Action::SetDotValue { dot, field, .. } => {
+                Some(format!("{}/{}", dot.get_name(), field.get_name()))
+            }
+            Action::SetDotColor { dot, field, .. } => {
+                Some(format!("{}/{}", dot.get_name(), field.get_name()))
+            }
+            Action::SetArcValue {
+                ring_index, field, ..
+            } => Some(format!(
+                "{}/{}",
+                get_arc_name(*ring_index),
+                field.get_name()
+            )),
+            Action::SetArcDial { ring_index, .. } => {
+                Some(format!("{}/dial", get_arc_name(*ring_index)))
+            }
+            Action::SetStyleColor { field, .. } => Some(format!("style/{}", field.get_name())),
+            Action::SetColorMode { .. } => Some("color_mode".to_string()),
+            Action::SetBackground { .. } => Some("background".to_string()),
+            _ => None,
+        };
+    }
+
+    /// Applies the action to the state, returning any warnings about the
+    /// result. On an error, the state is left as it was.
+    pub fn apply(self, state: &mut FullApplicationState) -> Result<Vec<String>, AppError> {
+        match self {
+            Action::SetText { text } => {
+                check_text(&text)?;
+                state.diagram.set_text(&text);
+            }
+            Action::SetRingText { ring_index, text } => {
+                check_text(&text)?;
+                get_ring_mut(&mut state.diagram, ring_index)?.set_text(text);
+            }
+            Action::AddRing { text } => {
+                check_text(&text)?;
+                state.diagram.paths.push(TextPath::new(text));
+            }
+            Action::RemoveRing { ring_index } => {
+                get_ring(&state.diagram, ring_index)?;
+                state.diagram.paths.remove(ring_index);
+            }
+            Action::SetDotValue { dot, field, value } => {
+                edit_dot(state, dot, |dot| set_dot_value(dot, field, value))?;
+            }
+            Action::SetDotColor { dot, field, color } => {
+                edit_dot(state, dot, |dot| {
+                    match field {
+                        DotColorField::FillColor => dot.fill_color = color,
+                        DotColorField::RingColor => dot.ring_color = color,
+                    };
+                    return Ok(());
+                })?;
+            }
+            Action::SetDotShape { dot, shape } => {
+                let shape: DotShape = match DotShape::from_name(&shape) {
+                    Some(shape) => shape,
+                    None => return Err(AppError::unknown_id("shape", &shape)),
+                };
+                edit_dot(state, dot, |dot| {
+                    dot.shape = shape;
+                    return Ok(());
+                })?;
+            }
+            Action::ImportDotGlyph { dot, source } => {
+                let glyph: Glyph = match Glyph::from_source(&source) {
+                    Some(glyph) => glyph,
+                    None => {
+                        return Err(AppError::invalid_value(
+                            "glyph",
+                            "has no usable path data, or a view box with no area",
+                        ))
+                    }
+                };
+                edit_dot(state, dot, |dot| {
+                    dot.shape = DotShape::Glyph(glyph);
+                    return Ok(());
+                })?;
+            }
+            Action::SetArcValue {
+                ring_index,
+                field,
+                value,
+            } => {
+                edit_arc(state, ring_index, |arc_style| {
+                    return set_arc_value(arc_style, field, value);
+                })?;
+            }
+            Action::SetArcDial {
+                ring_index,
+                arc_offset_percentage,
+                arc_percentage,
+            } => {
+                edit_arc(state, ring_index, |arc_style| {
+                    set_arc_value(
+                        arc_style,
+                        ArcField::ArcOffsetPercentage,
+                        arc_offset_percentage,
+                    )?;
+                    return set_arc_value(arc_style, ArcField::ArcPercentage, arc_percentage);
+                })?;
+            }
+            Action::SetStyleColor { field, color } => match field {
+                StyleColorField::StrokeColor => state.style.stroke_color = color,
+                StyleColorField::BackgroundColor => state.style.background_color = color,
+            },
+            Action::SetColorMode { mode, colors } => {
+                let colors: Vec<Color> = match parse_color_list(&colors) {
+                    Some(colors) => colors,
+                    None => return Err(AppError::invalid_value("colors", "are not css colors")),
+                };
+                state.style.color_mode = match ColorMode::from_name(&mode, colors) {
+                    Some(color_mode) => color_mode,
+                    None => {
+                        return Err(AppError::invalid_value(
+                            "colors",
+                            &format!("are the wrong number for the color mode {:?}", mode),
+                        ));
+                    }
+                };
+            }
+            Action::SetBackground {
+                shape,
+                padding,
+                print_scale,
+            } => {
+                if !(padding.value >= 0.0) || !(print_scale > 0.0) {
+                    return Err(AppError::invalid_value(
+                        "background",
+                        "padding and print scale must be positive",
+                    ));
+                }
+                state.diagram.background.shape = shape;
+                state.diagram.background.padding = padding;
+                state.diagram.print_scale = print_scale;
+            }
+            Action::SetCenterImage { data_url } => {
+                if let Some(data_url) = &data_url {
+                    if !is_image_data_url(data_url) {
+                        return Err(AppError::invalid_value(
+                            "center_image",
+                            "must be a base64 data url of a png, jpeg, gif, webp or svg image",
+                        ));
+                    }
+                }
+                state.diagram.background.center_image = data_url;
+            }
+            Action::SetExposeText { expose_text } => {
+                state.diagram.expose_text = expose_text;
+            }
+            Action::SetPreviewVision { vision } => {
+                state.preview_vision = vision;
+            }
+            Action::LoadDocument { source, format } => {
+                let loaded: LoadedDocument =
+                    Document::load(&source, &format).map_err(AppError::Document)?;
+                return Ok(load_design(state, loaded));
+            }
+            Action::ImportSvg { source } => {
+                match load_embedded_document(&source) {
+                    Some(Ok(loaded)) => return Ok(load_design(state, loaded)),
+                    Some(Err(error)) => return Err(AppError::Document(error)),
+                    None => {}
+                };
+                let lines: Vec<String> = find_ring_labels(&source);
+                if lines.is_empty() {
+                    return Err(AppError::NothingToImport);
+                }
+                for line in &lines {
+                    check_text(line)?;
+                }
+                state.diagram.paths = lines.into_iter().map(TextPath::new).collect();
+            }
+            Action::ApplyTheme { theme } => match get_theme(&theme) {
+                Some(theme) => state.style.apply_theme(theme),
+                None => return Err(AppError::unknown_id("theme", &theme)),
+            },
+            Action::SavePreset { name } => {
+                let name: &str = name.trim();
+                if name.is_empty() {
+                    return Err(AppError::invalid_value("preset name", "is empty"));
+                }
+                state.style.save_preset(name);
+            }
+            Action::SetRingPreset { ring_index, preset } => {
+                if let Some(preset) = &preset {
+                    if state.style.get_preset(preset).is_none() {
+                        return Err(AppError::unknown_id("preset", preset));
+                    }
+                }
+                get_ring_mut(&mut state.diagram, ring_index)?.preset = preset;
+            }
+            Action::SetRingOverride { ring_index, part } => {
+                let path: &TextPath = get_ring(&state.diagram, ring_index)?;
+                let (zero_dot_style, one_dot_style, arc_style) = (
+                    path.resolve_dot_style(&state.style, false, None).to_dot(),
+                    path.resolve_dot_style(&state.style, true, None).to_dot(),
+                    path.resolve_arc_style(&state.style).to_arc_style(),
+                );
+                let path: &mut TextPath = get_ring_mut(&mut state.diagram, ring_index)?;
+                match part {
+                    RingStylePart::ZeroDotStyle => {
+                        path.zero_dot_style = DotOverride::from_dot(&zero_dot_style)
+                    }
+                    RingStylePart::OneDotStyle => {
+                        path.one_dot_style = DotOverride::from_dot(&one_dot_style)
+                    }
+                    RingStylePart::ArcStyle => {
+                        path.arc_style = ArcOverride::from_arc_style(&arc_style)
+                    }
+                };
+            }
+            Action::ClearRingOverride { ring_index, part } => {
+                let path: &mut TextPath = get_ring_mut(&mut state.diagram, ring_index)?;
+                match part {
+                    RingStylePart::ZeroDotStyle => path.zero_dot_style = DotOverride::default(),
+                    RingStylePart::OneDotStyle => path.one_dot_style = DotOverride::default(),
+                    RingStylePart::ArcStyle => path.arc_style = ArcOverride::default(),
+                };
+            }
+        };
+        return Ok(vec![]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fig::text_path::{DotTarget, TargetedDotOverride};
+    use crate::start::get_initial_state;
+
+    fn get_state_with_override() -> FullApplicationState {
+        let mut state: FullApplicationState = get_initial_state();
+        state.diagram.paths[0].dot_overrides = vec![TargetedDotOverride {
+            target: DotTarget::Character(1),
+            style: DotOverride::default(),
+        }];
+        return state;
+    }
+
+    #[test]
+    fn set_ring_text_keeps_overrides_when_the_text_is_unchanged() {
+        let mut state: FullApplicationState = get_state_with_override();
+        let text: String = state.diagram.paths[0].text.clone();
+        Action::SetRingText {
+            ring_index: 0,
+            text: text,
+        }
+        .apply(&mut state)
+        .unwrap();
+        assert_eq!(state.diagram.paths[0].dot_overrides.len(), 1);
+    }
+
+    #[test]
+    fn set_ring_text_drops_overrides_placed_for_the_old_text() {
+        let mut state: FullApplicationState = get_state_with_override();
+        Action::SetRingText {
+            ring_index: 0,
+            text: "h".to_string(),
+        }
+        .apply(&mut state)
+        .unwrap();
+        assert_eq!(state.diagram.paths[0].text, "h");
+        assert!(state.diagram.paths[0].dot_overrides.is_empty());
+    }
+
+    #[test]
+    fn set_ring_text_rejects_text_that_cannot_be_encoded() {
+        let mut state: FullApplicationState = get_state_with_override();
+        let result = Action::SetRingText {
+            ring_index: 0,
+            text: "héllo".to_string(),
+        }
+        .apply(&mut state);
+        assert!(result.is_err());
+        assert_eq!(state.diagram.paths[0].dot_overrides.len(), 1);
+    }
+}
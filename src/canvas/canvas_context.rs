@@ -0,0 +1,20 @@
+use crate::color::Color;
+use crate::geom::{Rect, Vector2};
+
+/// The subset of 2d canvas drawing commands used to draw diagrams.
+///
+/// Circles are drawn at the current origin, so drawables position
+/// themselves with `translate` the same way svg groups do.
+pub trait CanvasContext {
+    fn save(&mut self);
+    fn restore(&mut self);
+    fn translate(&mut self, x: f64, y: f64);
+    fn scale(&mut self, x: f64, y: f64);
+    /// Sets the opacity of everything drawn until the next `restore`
+    fn set_opacity(&mut self, opacity: f64);
+    fn fill_rect(&mut self, rect: &Rect, color: &Color);
+    fn fill_circle(&mut self, radius: f64, color: &Color);
+    /// Fills closed polygons using the nonzero winding rule
+    fn fill_polygons(&mut self, polygons: &Vec<Vec<Vector2>>, color: &Color);
+    fn stroke_circle(&mut self, radius: f64, stroke_width: f64, color: &Color);
+}
@@ -0,0 +1,6 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::drawing_style::DrawingStyle;
+
+pub trait CanvasDrawable {
+    fn draw(&self, context: &mut dyn CanvasContext, style: &DrawingStyle);
+}
@@ -0,0 +1,4 @@
+pub mod canvas_context;
+pub mod canvas_drawable;
+pub mod recording_context;
+pub mod web_canvas;
@@ -0,0 +1,302 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::color::Color;
+use crate::geom::{Rect, Vector2};
+
+#[derive(Debug, PartialEq)]
+pub enum DrawCommand {
+    Save,
+    Restore,
+    Translate {
+        x: f64,
+        y: f64,
+    },
+    Scale {
+        x: f64,
+        y: f64,
+    },
+    SetOpacity {
+        opacity: f64,
+    },
+    FillRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: Color,
+    },
+    FillCircle {
+        radius: f64,
+        color: Color,
+    },
+    FillPolygons {
+        polygons: Vec<Vec<Vector2>>,
+        color: Color,
+    },
+    StrokeCircle {
+        radius: f64,
+        stroke_width: f64,
+        color: Color,
+    },
+}
+
+/// A canvas context that records the commands issued to it instead of
+/// drawing them, so drawing can be inspected without a browser.
+pub struct RecordingContext {
+    pub commands: Vec<DrawCommand>,
+}
+
+impl RecordingContext {
+    pub fn new() -> RecordingContext {
+        return RecordingContext { commands: vec![] };
+    }
+}
+
+impl CanvasContext for RecordingContext {
+    fn save(&mut self) {
+        self.commands.push(DrawCommand::Save);
+    }
+
+    fn restore(&mut self) {
+        self.commands.push(DrawCommand::Restore);
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        self.commands.push(DrawCommand::Translate { x: x, y: y });
+    }
+
+    fn scale(&mut self, x: f64, y: f64) {
+        self.commands.push(DrawCommand::Scale { x: x, y: y });
+    }
+
+    fn set_opacity(&mut self, opacity: f64) {
+        self.commands
+            .push(DrawCommand::SetOpacity { opacity: opacity });
+    }
+
+    fn fill_rect(&mut self, rect: &Rect, color: &Color) {
+        self.commands.push(DrawCommand::FillRect {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+            color: *color,
+        });
+    }
+
+    fn fill_circle(&mut self, radius: f64, color: &Color) {
+        self.commands.push(DrawCommand::FillCircle {
+            radius: radius,
+            color: *color,
+        });
+    }
+
+    fn fill_polygons(&mut self, polygons: &Vec<Vec<Vector2>>, color: &Color) {
+        self.commands.push(DrawCommand::FillPolygons {
+            polygons: polygons.clone(),
+            color: *color,
+        });
+    }
+
+    fn stroke_circle(&mut self, radius: f64, stroke_width: f64, color: &Color) {
+        self.commands.push(DrawCommand::StrokeCircle {
+            radius: radius,
+            stroke_width: stroke_width,
+            color: *color,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::canvas_drawable::CanvasDrawable;
+    use crate::canvas::web_canvas::draw_fitted_diagram;
+    use crate::drawing_style::DrawingStyle;
+    use crate::fig::background::{Background, BackgroundShape};
+    use crate::fig::diagram::Diagram;
+    use crate::fig::text_path::{DotPlacement, TextPath};
+    use crate::geom::{Length, LengthUnit};
+    use crate::themes::get_theme;
+
+    /// A single ring spelling "A", which is 0b01000001: two one dots and
+    /// six zero dots
+    fn get_test_diagram() -> Diagram {
+        return Diagram {
+            paths: vec![TextPath::new("A".to_string())],
+            background: Background {
+                shape: BackgroundShape::Rectangle,
+                padding: Length {
+                    value: 2.0,
+                    unit: LengthUnit::Units,
+                },
+                center_image: None,
+            },
+            print_scale: 5.0,
+            expose_text: true,
+        };
+    }
+
+    /// The commands drawing one dot, as `TextPath::draw_ring` issues them
+    fn get_dot_commands(placement: &DotPlacement) -> Vec<DrawCommand> {
+        return vec![
+            DrawCommand::Save,
+            DrawCommand::Translate {
+                x: placement.x,
+                y: placement.y,
+            },
+            DrawCommand::Save,
+            DrawCommand::SetOpacity {
+                opacity: placement.dot.get_opacity(),
+            },
+            DrawCommand::FillCircle {
+                radius: placement.dot.circle_radius,
+                color: placement.dot.get_fill_color(&placement.color),
+            },
+            DrawCommand::StrokeCircle {
+                radius: placement.dot.ring_radius,
+                stroke_width: placement.dot.ring_stroke_width,
+                color: placement.dot.get_ring_color(&placement.color),
+            },
+            DrawCommand::Restore,
+            DrawCommand::Restore,
+        ];
+    }
+
+    #[test]
+    fn draws_the_background_then_each_dot_around_the_center() {
+        let style: DrawingStyle = get_theme("light").unwrap();
+        let diagram: Diagram = get_test_diagram();
+        let mut context: RecordingContext = RecordingContext::new();
+        diagram.draw(&mut context, &style);
+
+        let bounds: Rect = diagram.get_bounding_rect(&style);
+        let center: Vector2 = bounds.center();
+        let placements: Vec<DotPlacement> = diagram.paths[0].get_dot_placements(&style, 0, 1);
+        assert_eq!(placements.len(), 8);
+
+        let mut expected: Vec<DrawCommand> = vec![
+            DrawCommand::FillRect {
+                x: bounds.x,
+                y: bounds.y,
+                width: bounds.width,
+                height: bounds.height,
+                color: style.background_color,
+            },
+            DrawCommand::Save,
+            DrawCommand::Translate {
+                x: center.x,
+                y: center.y,
+            },
+        ];
+        for placement in placements.iter() {
+            expected.extend(get_dot_commands(placement));
+        }
+        expected.push(DrawCommand::Restore);
+        assert_eq!(context.commands, expected);
+
+        // one filled circle and one ring per dot, sized by its value
+        let fills: Vec<f64> = context
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::FillCircle { radius, .. } => Some(*radius),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fills, vec![0.5, 1.0, 1.0, 1.0, 1.0, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn draws_dots_in_their_resolved_colors() {
+        let style: DrawingStyle = get_theme("light").unwrap();
+        let mut diagram: Diagram = get_test_diagram();
+        let fill_color: Color = Color::from_rgb(0xd6, 0x28, 0x28);
+        let ring_color: Color = Color::from_rgb(0x00, 0x30, 0x49);
+        diagram.paths[0].one_dot_style.fill_color = Some(fill_color);
+        diagram.paths[0].zero_dot_style.ring_color = Some(ring_color);
+
+        let mut context: RecordingContext = RecordingContext::new();
+        diagram.draw(&mut context, &style);
+
+        let fill_colors: Vec<Color> = context
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::FillCircle { color, .. } => Some(*color),
+                _ => None,
+            })
+            .collect();
+        let ring_colors: Vec<Color> = context
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::StrokeCircle { color, .. } => Some(*color),
+                _ => None,
+            })
+            .collect();
+        let stroke_color: Color = style.stroke_color;
+        assert_eq!(
+            fill_colors,
+            vec![
+                fill_color,
+                stroke_color,
+                stroke_color,
+                stroke_color,
+                stroke_color,
+                stroke_color,
+                fill_color,
+                stroke_color,
+            ]
+        );
+        assert_eq!(
+            ring_colors,
+            vec![
+                stroke_color,
+                ring_color,
+                ring_color,
+                ring_color,
+                ring_color,
+                ring_color,
+                stroke_color,
+                ring_color,
+            ]
+        );
+    }
+
+    #[test]
+    fn scales_by_the_device_pixel_ratio_and_centers_the_diagram() {
+        let style: DrawingStyle = get_theme("light").unwrap();
+        let diagram: Diagram = get_test_diagram();
+        let mut context: RecordingContext = RecordingContext::new();
+        draw_fitted_diagram(&mut context, &diagram, &style, 200.0, 100.0, 2.0);
+
+        // the diagram is square, so it is fit to the height and centered
+        // across the width
+        let bounds: Rect = diagram.get_bounding_rect(&style);
+        let fit_scale: f64 = 100.0 / bounds.height;
+        assert_eq!(
+            &context.commands[..5],
+            &[
+                DrawCommand::Save,
+                DrawCommand::Scale { x: 2.0, y: 2.0 },
+                DrawCommand::Translate { x: 50.0, y: 0.0 },
+                DrawCommand::Scale {
+                    x: fit_scale,
+                    y: fit_scale,
+                },
+                DrawCommand::Translate {
+                    x: -bounds.x,
+                    y: -bounds.y,
+                },
+            ]
+        );
+        assert_eq!(context.commands.last(), Some(&DrawCommand::Restore));
+
+        let mut drawn: RecordingContext = RecordingContext::new();
+        diagram.draw(&mut drawn, &style);
+        assert_eq!(
+            &context.commands[5..context.commands.len() - 1],
+            &drawn.commands[..]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::canvas::canvas_drawable::CanvasDrawable;
+use crate::color::Color;
+use crate::drawing_style::DrawingStyle;
+use crate::fig::diagram::Diagram;
+use crate::float_utils::fmin;
+use crate::geom::{Rect, Vector2};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+// Calls to the inherent CanvasRenderingContext2d methods only fail for
+// non-finite arguments, in which case there is nothing useful to draw.
+impl CanvasContext for CanvasRenderingContext2d {
+    fn save(&mut self) {
+        CanvasRenderingContext2d::save(self);
+    }
+
+    fn restore(&mut self) {
+        CanvasRenderingContext2d::restore(self);
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        let _ = CanvasRenderingContext2d::translate(self, x, y);
+    }
+
+    fn scale(&mut self, x: f64, y: f64) {
+        let _ = CanvasRenderingContext2d::scale(self, x, y);
+    }
+
+    fn set_opacity(&mut self, opacity: f64) {
+        self.set_global_alpha(opacity);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect, color: &Color) {
+        self.set_fill_style(&JsValue::from_str(&color.to_css()));
+        CanvasRenderingContext2d::fill_rect(self, rect.x, rect.y, rect.width, rect.height);
+    }
+
+    fn fill_circle(&mut self, radius: f64, color: &Color) {
+        if radius <= 0.0 {
+            return;
+        }
+        self.set_fill_style(&JsValue::from_str(&color.to_css()));
+        self.begin_path();
+        let _ = self.arc(0.0, 0.0, radius, 0.0, std::f64::consts::PI * 2.0);
+        self.fill();
+    }
+
+    fn fill_polygons(&mut self, polygons: &Vec<Vec<Vector2>>, color: &Color) {
+        self.set_fill_style(&JsValue::from_str(&color.to_css()));
+        self.begin_path();
+        for polygon in polygons.iter() {
+            for (index, point) in polygon.iter().enumerate() {
+                if index == 0 {
+                    self.move_to(point.x, point.y);
+                } else {
+                    self.line_to(point.x, point.y);
+                }
+            }
+            self.close_path();
+        }
+        self.fill();
+    }
+
+    fn stroke_circle(&mut self, radius: f64, stroke_width: f64, color: &Color) {
+        // a line width of 0 is ignored by the canvas rather than
+        // drawing nothing, so skip the stroke entirely
+        if radius <= 0.0 || stroke_width <= 0.0 {
+            return;
+        }
+        self.set_stroke_style(&JsValue::from_str(&color.to_css()));
+        self.set_line_width(stroke_width);
+        self.begin_path();
+        let _ = self.arc(0.0, 0.0, radius, 0.0, std::f64::consts::PI * 2.0);
+        self.stroke();
+    }
+}
+
+/// Draws the diagram fit to a `css_width` by `css_height` area, scaled
+/// up by the device pixel ratio to stay sharp
+pub fn draw_fitted_diagram(
+    target: &mut dyn CanvasContext,
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    css_width: f64,
+    css_height: f64,
+    device_pixel_ratio: f64,
+) {
+    let diagram_bounds: Rect = diagram.get_bounding_rect(style);
+    let fit_scale: f64 = fmin(
+        &(css_width / diagram_bounds.width),
+        &(css_height / diagram_bounds.height),
+    );
+
+    target.save();
+    target.scale(device_pixel_ratio, device_pixel_ratio);
+    // center the diagram in the element, like svg's default
+    // preserveAspectRatio
+    target.translate(
+        (css_width - diagram_bounds.width * fit_scale) / 2.0,
+        (css_height - diagram_bounds.height * fit_scale) / 2.0,
+    );
+    target.scale(fit_scale, fit_scale);
+    target.translate(-diagram_bounds.x, -diagram_bounds.y);
+    diagram.draw(target, style);
+    target.restore();
+}
+
+/// Draws the diagram into a canvas element, fit to the element's css
+/// size and scaled up by the device pixel ratio to stay sharp.
+///
+/// Returns false if a 2d context could not be acquired.
+pub fn paint_diagram_to_canvas(
+    canvas: &HtmlCanvasElement,
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    device_pixel_ratio: f64,
+) -> bool {
+    let css_width: f64 = canvas.client_width() as f64;
+    let css_height: f64 = canvas.client_height() as f64;
+    canvas.set_width((css_width * device_pixel_ratio) as u32);
+    canvas.set_height((css_height * device_pixel_ratio) as u32);
+
+    let mut context: CanvasRenderingContext2d = match canvas.get_context("2d") {
+        Ok(Some(context)) => match context.dyn_into::<CanvasRenderingContext2d>() {
+            Ok(context) => context,
+            Err(_) => return false,
+        },
+        _ => return false,
+    };
+
+    draw_fitted_diagram(
+        &mut context,
+        diagram,
+        style,
+        css_width,
+        css_height,
+        device_pixel_ratio,
+    );
+    return true;
+}
@@ -0,0 +1,655 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An sRGB color, with each channel and alpha from 0 to 1
+///
+/// Colors only enter the app through `parse`, so anything written into
+/// svg attributes or css comes from `to_css` and can't carry markup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub alpha: f64,
+}
+
+/// A color in the OKLCh space, the polar form of Oklab, where equal
+/// steps look like roughly equal changes in color
+#[derive(Debug, Clone, Copy)]
+struct Oklch {
+    lightness: f64,
+    chroma: f64,
+    /// In radians
+    hue: f64,
+    alpha: f64,
+}
+
+/// Below this chroma a color is effectively gray, and its hue is noise
+const ACHROMATIC_CHROMA: f64 = 0.0001;
+
+fn clamp_unit(value: f64) -> f64 {
+    return f64::min(f64::max(value, 0.0), 1.0);
+}
+
+/// Parses 3, 4, 6 or 8 hex digits, without the leading `#`
+fn parse_hex(hex: &str) -> Option<Color> {
+    let length: usize = match hex.len() {
+        3 | 4 => 1,
+        6 | 8 => 2,
+        _ => return None,
+    };
+    let channel = |index: usize| -> Option<f64> {
+        let digits = hex.get(index * length..(index + 1) * length)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u8::from_str_radix(digits, 16).ok()?;
+        return Some(if length == 1 { value * 17 } else { value } as f64 / 255.0);
+    };
+
+    return Some(Color {
+        red: channel(0)?,
+        green: channel(1)?,
+        blue: channel(2)?,
+        alpha: if hex.len() == 4 || hex.len() == 8 {
+            channel(3)?
+        } else {
+            1.0
+        },
+    });
+}
+
+/// Splits the arguments of a color function into its channels and
+/// optional alpha, accepting both `a, b, c, alpha` and `a b c / alpha`
+fn split_color_arguments(arguments: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    let (channels, alpha): (&str, Option<&str>) = match arguments.find('/') {
+        Some(slash) => (&arguments[..slash], Some(arguments[slash + 1..].trim())),
+        None => (arguments, None),
+    };
+    let mut channels: Vec<&str> = channels
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    return match (channels.len(), alpha) {
+        (3, _) => Some((channels, alpha)),
+        (4, None) => {
+            let alpha = channels.pop();
+            Some((channels, alpha))
+        }
+        _ => None,
+    };
+}
+
+/// Parses a number, or a percentage scaled so 100% is `full_scale`
+fn parse_number_or_percentage(value: &str, full_scale: f64) -> Option<f64> {
+    let number: f64 = if value.ends_with('%') {
+        value[..value.len() - 1].parse::<f64>().ok()? / 100.0 * full_scale
+    } else {
+        value.parse().ok()?
+    };
+    return if number.is_finite() {
+        Some(number)
+    } else {
+        None
+    };
+}
+
+fn parse_alpha(alpha: Option<&str>) -> Option<f64> {
+    return match alpha {
+        Some(alpha) => Some(clamp_unit(parse_number_or_percentage(alpha, 1.0)?)),
+        None => Some(1.0),
+    };
+}
+
+fn parse_rgb_function(arguments: &str) -> Option<Color> {
+    let (channels, alpha) = split_color_arguments(arguments)?;
+    let channel = |index: usize| -> Option<f64> {
+        return Some(clamp_unit(
+            parse_number_or_percentage(channels[index], 255.0)? / 255.0,
+        ));
+    };
+    return Some(Color {
+        red: channel(0)?,
+        green: channel(1)?,
+        blue: channel(2)?,
+        alpha: parse_alpha(alpha)?,
+    });
+}
+
+fn parse_hsl_function(arguments: &str) -> Option<Color> {
+    let (channels, alpha) = split_color_arguments(arguments)?;
+    let hue_text: &str = channels[0];
+    let hue_degrees: f64 = if hue_text.ends_with("deg") {
+        hue_text[..hue_text.len() - 3].parse().ok()?
+    } else if hue_text.ends_with("turn") {
+        hue_text[..hue_text.len() - 4].parse::<f64>().ok()? * 360.0
+    } else if hue_text.ends_with("rad") {
+        hue_text[..hue_text.len() - 3]
+            .parse::<f64>()
+            .ok()?
+            .to_degrees()
+    } else {
+        hue_text.parse().ok()?
+    };
+    if !hue_degrees.is_finite() || !channels[1].ends_with('%') || !channels[2].ends_with('%') {
+        return None;
+    }
+    let saturation: f64 = clamp_unit(parse_number_or_percentage(channels[1], 1.0)?);
+    let lightness: f64 = clamp_unit(parse_number_or_percentage(channels[2], 1.0)?);
+
+    // https://www.w3.org/TR/css-color-4/#hsl-to-rgb
+    let hue_to_channel = |offset: f64| {
+        let k = (offset + hue_degrees.rem_euclid(360.0) / 30.0) % 12.0;
+        let a = saturation * f64::min(lightness, 1.0 - lightness);
+        return lightness - a * f64::max(-1.0, f64::min(f64::min(k - 3.0, 9.0 - k), 1.0));
+    };
+    return Some(Color {
+        red: hue_to_channel(0.0),
+        green: hue_to_channel(8.0),
+        blue: hue_to_channel(4.0),
+        alpha: parse_alpha(alpha)?,
+    });
+}
+
+fn srgb_to_linear(channel: f64) -> f64 {
+    return if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        f64::powf((channel + 0.055) / 1.055, 2.4)
+    };
+}
+
+fn linear_to_srgb(channel: f64) -> f64 {
+    return if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * f64::powf(channel, 1.0 / 2.4) - 0.055
+    };
+}
+
+impl Color {
+    pub fn from_rgb(red: u8, green: u8, blue: u8) -> Color {
+        return Color {
+            red: red as f64 / 255.0,
+            green: green as f64 / 255.0,
+            blue: blue as f64 / 255.0,
+            alpha: 1.0,
+        };
+    }
+
+    /// Parses a css color: `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`,
+    /// `rgb()`, `rgba()`, `hsl()`, `hsla()` or a named color.
+    ///
+    /// Returns None for anything else.
+    pub fn parse(color: &str) -> Option<Color> {
+        let color: String = color.trim().to_ascii_lowercase();
+        if color.starts_with('#') {
+            return parse_hex(&color[1..]);
+        }
+        if let Some(open) = color.find('(') {
+            if !color.ends_with(')') {
+                return None;
+            }
+            let arguments: &str = &color[open + 1..color.len() - 1];
+            return match &color[..open] {
+                "rgb" | "rgba" => parse_rgb_function(arguments),
+                "hsl" | "hsla" => parse_hsl_function(arguments),
+                _ => None,
+            };
+        }
+        if color == "transparent" {
+            return Some(Color {
+                alpha: 0.0,
+                ..Color::from_rgb(0, 0, 0)
+            });
+        }
+        return NAMED_COLORS
+            .iter()
+            .find(|(name, _)| *name == color)
+            .map(|(_, rgb)| Color::from_rgb((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8));
+    }
+
+    /// Parses the value of an `<input type="color">`, which is always
+    /// `#rrggbb`
+    pub fn from_input_value(value: &str) -> Option<Color> {
+        if value.len() != 7 || !value.starts_with('#') {
+            return None;
+        }
+        return parse_hex(&value[1..]);
+    }
+
+    /// The color as an `<input type="color">` value. Inputs can't hold
+    /// alpha, so it is dropped.
+    pub fn to_input_value(&self) -> String {
+        let [red, green, blue, _] = self.to_rgba8();
+        return format!("#{:02x}{:02x}{:02x}", red, green, blue);
+    }
+
+    /// The canonical css form of the color: `#rrggbb` when opaque, and
+    /// `rgba()` otherwise, which more svg tools understand than
+    /// `#rrggbbaa`
+    pub fn to_css(&self) -> String {
+        if self.alpha >= 1.0 {
+            return self.to_input_value();
+        }
+        let [red, green, blue, _] = self.to_rgba8();
+        return format!(
+            "rgba({}, {}, {}, {})",
+            red,
+            green,
+            blue,
+            (clamp_unit(self.alpha) * 1000.0).round() / 1000.0
+        );
+    }
+
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let channel = |value: f64| (clamp_unit(value) * 255.0).round() as u8;
+        return [
+            channel(self.red),
+            channel(self.green),
+            channel(self.blue),
+            channel(self.alpha),
+        ];
+    }
+
+    /// Conversion matrices from https://bottosson.github.io/posts/oklab/
+    fn to_oklch(&self) -> Oklch {
+        let red = srgb_to_linear(self.red);
+        let green = srgb_to_linear(self.green);
+        let blue = srgb_to_linear(self.blue);
+
+        let l = f64::cbrt(0.4122214708 * red + 0.5363325363 * green + 0.0514459929 * blue);
+        let m = f64::cbrt(0.2119034982 * red + 0.6806995451 * green + 0.1073969566 * blue);
+        let s = f64::cbrt(0.0883024619 * red + 0.2817188376 * green + 0.6299787005 * blue);
+
+        let a = 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s;
+        let b = 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s;
+        return Oklch {
+            alpha: self.alpha,
+            lightness: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            chroma: f64::sqrt(a * a + b * b),
+            hue: f64::atan2(b, a),
+        };
+    }
+
+    fn from_oklch(color: &Oklch) -> Color {
+        let a = color.chroma * f64::cos(color.hue);
+        let b = color.chroma * f64::sin(color.hue);
+
+        let l = color.lightness + 0.3963377774 * a + 0.2158037573 * b;
+        let m = color.lightness - 0.1055613458 * a - 0.0638541728 * b;
+        let s = color.lightness - 0.0894841775 * a - 1.2914855480 * b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+        return Color {
+            red: linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            green: linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            blue: linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+            alpha: color.alpha,
+        };
+    }
+}
+
+/// Kinds of color vision, for simulating how colors look to people
+/// with color blindness
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorVision {
+    Normal,
+    /// No red cones
+    Protanopia,
+    /// No green cones
+    Deuteranopia,
+    /// No blue cones
+    Tritanopia,
+}
+
+/// The kinds of color blindness checked for and previewed
+pub const COLOR_BLIND_VISIONS: [ColorVision; 3] = [
+    ColorVision::Protanopia,
+    ColorVision::Deuteranopia,
+    ColorVision::Tritanopia,
+];
+
+impl ColorVision {
+    pub fn from_name(name: &str) -> Option<ColorVision> {
+        return match name {
+            "normal" => Some(ColorVision::Normal),
+            "protanopia" => Some(ColorVision::Protanopia),
+            "deuteranopia" => Some(ColorVision::Deuteranopia),
+            "tritanopia" => Some(ColorVision::Tritanopia),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ColorVision::Normal => "normal",
+            ColorVision::Protanopia => "protanopia",
+            ColorVision::Deuteranopia => "deuteranopia",
+            ColorVision::Tritanopia => "tritanopia",
+        };
+    }
+
+    /// Full severity simulation matrices for linear rgb, from Machado,
+    /// Oliveira and Fernandes, "A Physiologically-based Model for
+    /// Simulation of Color Vision Deficiency" (2009)
+    fn get_matrix(&self) -> Option<[[f64; 3]; 3]> {
+        return match self {
+            ColorVision::Normal => None,
+            ColorVision::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            ColorVision::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            ColorVision::Tritanopia => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+        };
+    }
+}
+
+impl Color {
+    /// How the color looks with the given kind of color vision
+    pub fn simulate_vision(&self, vision: &ColorVision) -> Color {
+        let matrix = match vision.get_matrix() {
+            Some(matrix) => matrix,
+            None => return *self,
+        };
+        let linear: [f64; 3] = [
+            srgb_to_linear(self.red),
+            srgb_to_linear(self.green),
+            srgb_to_linear(self.blue),
+        ];
+        let channel = |row: usize| {
+            let value = matrix[row][0] * linear[0]
+                + matrix[row][1] * linear[1]
+                + matrix[row][2] * linear[2];
+            return linear_to_srgb(clamp_unit(value));
+        };
+
+        return Color {
+            red: channel(0),
+            green: channel(1),
+            blue: channel(2),
+            alpha: self.alpha,
+        };
+    }
+
+    /// The opaque color seen when this color is drawn over `background`
+    pub fn composite_over(&self, background: &Color) -> Color {
+        let alpha = clamp_unit(self.alpha);
+        let blend = |channel: f64, background_channel: f64| {
+            channel * alpha + background_channel * (1.0 - alpha)
+        };
+        return Color {
+            red: blend(self.red, background.red),
+            green: blend(self.green, background.green),
+            blue: blend(self.blue, background.blue),
+            alpha: 1.0,
+        };
+    }
+
+    /// Relative luminance as defined by WCAG 2, ignoring alpha
+    pub fn get_relative_luminance(&self) -> f64 {
+        return 0.2126 * srgb_to_linear(self.red)
+            + 0.7152 * srgb_to_linear(self.green)
+            + 0.0722 * srgb_to_linear(self.blue);
+    }
+
+    /// The WCAG 2 contrast ratio between two colors, from 1 for equal
+    /// luminance to 21 for black on white
+    pub fn get_contrast_ratio(&self, other: &Color) -> f64 {
+        let luminance = self.get_relative_luminance();
+        let other_luminance = other.get_relative_luminance();
+        return (f64::max(luminance, other_luminance) + 0.05)
+            / (f64::min(luminance, other_luminance) + 0.05);
+    }
+
+    /// Perceptual difference between two colors: their distance in
+    /// Oklab, where 0.02 is about the smallest visible difference
+    pub fn get_difference(&self, other: &Color) -> f64 {
+        let color = self.to_oklch();
+        let other = other.to_oklch();
+        let a_delta = color.chroma * f64::cos(color.hue) - other.chroma * f64::cos(other.hue);
+        let b_delta = color.chroma * f64::sin(color.hue) - other.chroma * f64::sin(other.hue);
+        let lightness_delta = color.lightness - other.lightness;
+        return f64::sqrt(
+            lightness_delta * lightness_delta + a_delta * a_delta + b_delta * b_delta,
+        );
+    }
+}
+
+// Colors are saved in their canonical css form, so documents stay
+// readable and can be edited by hand
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&self.to_css());
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let color = String::deserialize(deserializer)?;
+        return Color::parse(&color)
+            .ok_or_else(|| D::Error::custom(format!("{} is not a css color", color)));
+    }
+}
+
+/// Parses a comma separated list of css colors. Commas inside color
+/// functions like `rgb()` don't split the list.
+///
+/// Returns None if any color in the list is invalid.
+pub fn parse_color_list(colors: &str) -> Option<Vec<Color>> {
+    let mut parsed: Vec<Color> = vec![];
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+    for (index, c) in colors.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parsed.push(Color::parse(&colors[start..index])?);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if !colors[start..].trim().is_empty() || !parsed.is_empty() {
+        parsed.push(Color::parse(&colors[start..])?);
+    }
+    return Some(parsed);
+}
+
+/// Blends from `start` at `t = 0` to `end` at `t = 1` in OKLCh, taking
+/// the shorter way around the hue wheel, so gradients sweep through
+/// hues instead of passing through gray.
+pub fn interpolate_colors(start: &Color, end: &Color, t: f64) -> Color {
+    let start = start.to_oklch();
+    let end = end.to_oklch();
+
+    // grays take the hue of the other end, so only chroma changes
+    let start_hue = if start.chroma < ACHROMATIC_CHROMA {
+        end.hue
+    } else {
+        start.hue
+    };
+    let end_hue = if end.chroma < ACHROMATIC_CHROMA {
+        start_hue
+    } else {
+        end.hue
+    };
+    let mut hue_delta = end_hue - start_hue;
+    if hue_delta > std::f64::consts::PI {
+        hue_delta -= std::f64::consts::PI * 2.0;
+    } else if hue_delta < -std::f64::consts::PI {
+        hue_delta += std::f64::consts::PI * 2.0;
+    }
+
+    return Color::from_oklch(&Oklch {
+        lightness: start.lightness + (end.lightness - start.lightness) * t,
+        chroma: start.chroma + (end.chroma - start.chroma) * t,
+        hue: start_hue + hue_delta * t,
+        alpha: start.alpha + (end.alpha - start.alpha) * t,
+    });
+}
+
+/// The css named colors, as 0xrrggbb
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
@@ -0,0 +1,180 @@
+use crate::color::{Color, ColorVision, COLOR_BLIND_VISIONS};
+use crate::drawing_style::DrawingStyle;
+use crate::fig::dot::Dot;
+use crate::fig::shape::ShapeGeometry;
+
+/// WCAG 2.1 asks graphical objects to have a contrast ratio of at least
+/// 3:1 against adjacent colors (success criterion 1.4.11)
+pub const MIN_GRAPHICS_CONTRAST_RATIO: f64 = 3.0;
+
+/// Colors closer than this in Oklab are hard to tell apart at the size
+/// of a dot, even if they are technically different
+pub const MIN_DISTINGUISHABLE_DIFFERENCE: f64 = 0.1;
+
+/// A comparison between two colors that are drawn next to each other
+#[derive(Debug)]
+pub struct ColorPairCheck {
+    /// What is being compared, like "one dot fill #333333"
+    pub name: String,
+    /// What it is compared against, like "the background #eeeeee"
+    pub against: String,
+    pub contrast_ratio: f64,
+    /// Whether the pair needs the contrast ratio for graphical objects.
+    /// Dots need it against the background, but zero and one dots only
+    /// need to look different from each other.
+    pub needs_contrast: bool,
+    /// Perceptual difference with normal color vision
+    pub difference: f64,
+    /// Kinds of color blindness that make the pair hard to tell apart,
+    /// although it is distinguishable with normal color vision
+    pub lost_under: Vec<ColorVision>,
+}
+
+impl ColorPairCheck {
+    /// Compares two colors as seen over `background`
+    fn new(
+        name: String,
+        against: String,
+        color: &Color,
+        other: &Color,
+        background: &Color,
+        needs_contrast: bool,
+    ) -> ColorPairCheck {
+        let color = color.composite_over(background);
+        let other = other.composite_over(background);
+        let difference: f64 = color.get_difference(&other);
+
+        let lost_under: Vec<ColorVision> = if difference < MIN_DISTINGUISHABLE_DIFFERENCE {
+            vec![]
+        } else {
+            COLOR_BLIND_VISIONS
+                .iter()
+                .filter(|vision| {
+                    color
+                        .simulate_vision(vision)
+                        .get_difference(&other.simulate_vision(vision))
+                        < MIN_DISTINGUISHABLE_DIFFERENCE
+                })
+                .cloned()
+                .collect()
+        };
+
+        return ColorPairCheck {
+            name: name,
+            against: against,
+            contrast_ratio: color.get_contrast_ratio(&other),
+            needs_contrast: needs_contrast,
+            difference: difference,
+            lost_under: lost_under,
+        };
+    }
+
+    pub fn is_low_contrast(&self) -> bool {
+        return self.needs_contrast && self.contrast_ratio < MIN_GRAPHICS_CONTRAST_RATIO;
+    }
+
+    /// Human readable descriptions of everything wrong with the pair
+    pub fn get_warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = vec![];
+        if self.is_low_contrast() {
+            warnings.push(format!(
+                "{} has a contrast ratio of {:.1}:1 against {}, below {}:1",
+                self.name, self.contrast_ratio, self.against, MIN_GRAPHICS_CONTRAST_RATIO
+            ));
+        }
+        for vision in self.lost_under.iter() {
+            warnings.push(format!(
+                "{} is hard to tell from {} with {}",
+                self.name,
+                self.against,
+                vision.get_name()
+            ));
+        }
+        return warnings;
+    }
+}
+
+/// The colors a dot is drawn with, named for reports
+fn get_dot_colors(dot: &Dot, dot_name: &str, mode_color: &Color) -> Vec<(String, Color)> {
+    let mut colors: Vec<(String, Color)> = vec![];
+    match dot.get_shape_geometry() {
+        ShapeGeometry::Empty => {}
+        _ => {
+            let fill_color = dot.get_fill_color(mode_color);
+            colors.push((
+                format!("{} fill {}", dot_name, fill_color.to_css()),
+                fill_color,
+            ));
+        }
+    }
+    if dot.ring_stroke_width > 0.0 {
+        let ring_color = dot.get_ring_color(mode_color);
+        colors.push((
+            format!("{} ring {}", dot_name, ring_color.to_css()),
+            ring_color,
+        ));
+    }
+    return colors;
+}
+
+/// Checks every color the style draws dots with against the
+/// background, and the zero dot's colors against the one dot's
+pub fn check_style_colors(style: &DrawingStyle) -> Vec<ColorPairCheck> {
+    let mut mode_colors: Vec<Color> = style.color_mode.get_colors();
+    if mode_colors.is_empty() {
+        mode_colors.push(style.stroke_color);
+    }
+    let background_name: String = format!("the background {}", style.background_color.to_css());
+
+    let mut checks: Vec<ColorPairCheck> = vec![];
+    for mode_color in mode_colors.iter() {
+        let zero_colors = get_dot_colors(&style.default_zero_dot_style, "zero dot", mode_color);
+        let one_colors = get_dot_colors(&style.default_one_dot_style, "one dot", mode_color);
+
+        for (name, color) in zero_colors.iter().chain(one_colors.iter()) {
+            // dots with their own colors come up once per mode color
+            if checks.iter().any(|check| &check.name == name) {
+                continue;
+            }
+            checks.push(ColorPairCheck::new(
+                name.clone(),
+                background_name.clone(),
+                color,
+                &style.background_color,
+                &style.background_color,
+                true,
+            ));
+        }
+
+        // dots are told apart by their most prominent color, the fill
+        // if they have one
+        if let (Some((zero_name, zero_color)), Some((one_name, one_color))) =
+            (zero_colors.first(), one_colors.first())
+        {
+            if checks
+                .iter()
+                .any(|check| &check.name == zero_name && &check.against == one_name)
+            {
+                continue;
+            }
+            checks.push(ColorPairCheck::new(
+                zero_name.clone(),
+                one_name.clone(),
+                zero_color,
+                one_color,
+                &style.background_color,
+                false,
+            ));
+        }
+    }
+
+    return checks;
+}
+
+/// Human readable warnings for every problem found in the style's colors
+pub fn get_style_color_warnings(style: &DrawingStyle) -> Vec<String> {
+    return check_style_colors(style)
+        .iter()
+        .flat_map(|check| check.get_warnings())
+        .collect();
+}
@@ -0,0 +1,148 @@
+use crate::color::Color;
+use crate::document::DOCUMENT_VERSION;
+use serde_json::{Map, Value};
+
+/// Upgrades a document from `from_version` to the version after it,
+/// noting anything it had to change along the way
+struct Migration {
+    from_version: u32,
+    migrate: fn(&mut Value, &mut Vec<String>),
+}
+
+/// Every migration, oldest first. Each one only knows about the version
+/// it upgrades from, so old documents are upgraded one step at a time.
+const MIGRATIONS: [Migration; 1] = [Migration {
+    from_version: 1,
+    migrate: colors_to_css,
+}];
+
+/// Upgrades a document saved as `version` to the current version
+pub fn migrate(document: &mut Value, version: u32, warnings: &mut Vec<String>) {
+    for migration in MIGRATIONS.iter() {
+        if migration.from_version >= version {
+            (migration.migrate)(document, warnings);
+        }
+    }
+    if let Some(document) = document.as_object_mut() {
+        document.insert("version".to_string(), Value::from(DOCUMENT_VERSION));
+    }
+}
+
+fn child_field(field: &str, key: &str) -> String {
+    if field.is_empty() {
+        return key.to_string();
+    }
+    return format!("{}.{}", field, key);
+}
+
+/// Reads a version 1 color, which was saved as its four channels
+fn get_channels(color: &Map<String, Value>) -> Option<[f64; 4]> {
+    if color.len() != 4 {
+        return None;
+    }
+    return Some([
+        color.get("red")?.as_f64()?,
+        color.get("green")?.as_f64()?,
+        color.get("blue")?.as_f64()?,
+        color.get("alpha")?.as_f64()?,
+    ]);
+}
+
+/// Version 2 saves colors as css instead of channels from 0 to 1, so
+/// they can be read and edited by hand
+fn colors_to_css(document: &mut Value, warnings: &mut Vec<String>) {
+    fn visit(field: &str, value: &mut Value, warnings: &mut Vec<String>) {
+        let channels: Option<[f64; 4]> = value.as_object().and_then(get_channels);
+        if let Some(channels) = channels {
+            if channels
+                .iter()
+                .any(|channel| !(*channel >= 0.0 && *channel <= 1.0))
+            {
+                warnings.push(format!(
+                    "{} had channels outside 0 to 1, which were clamped",
+                    field
+                ));
+            }
+            let [red, green, blue, alpha] = channels;
+            let color = Color {
+                red: red,
+                green: green,
+                blue: blue,
+                alpha: alpha,
+            };
+            *value = Value::from(color.to_css());
+            return;
+        }
+
+        match value {
+            Value::Object(fields) => {
+                for (key, child) in fields.iter_mut() {
+                    visit(&child_field(field, key), child, warnings);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    visit(&format!("{}[{}]", field, index), item, warnings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    visit("", document, warnings);
+}
+
+/// Whether a value carries nothing, so losing or gaining it changes
+/// nothing about the design
+fn is_blank(value: &Value) -> bool {
+    return match value {
+        Value::Null => true,
+        Value::Object(fields) => fields.values().all(is_blank),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    };
+}
+
+/// Compares a migrated document with how the loaded document saves
+/// again, warning about fields this version ignored or filled in
+pub fn compare_fields(field: &str, migrated: &Value, loaded: &Value, warnings: &mut Vec<String>) {
+    match (migrated, loaded) {
+        (Value::Object(migrated_fields), Value::Object(loaded_fields)) => {
+            for (key, migrated_value) in migrated_fields.iter() {
+                let key_field: String = child_field(field, key);
+                match loaded_fields.get(key) {
+                    Some(loaded_value) => {
+                        compare_fields(&key_field, migrated_value, loaded_value, warnings)
+                    }
+                    None if !is_blank(migrated_value) => warnings.push(format!(
+                        "dropped {}, which this version doesn't use",
+                        key_field
+                    )),
+                    None => {}
+                }
+            }
+            for (key, loaded_value) in loaded_fields.iter() {
+                if !migrated_fields.contains_key(key) && !is_blank(loaded_value) {
+                    warnings.push(format!(
+                        "{} was missing, and defaulted to {}",
+                        child_field(field, key),
+                        loaded_value
+                    ));
+                }
+            }
+        }
+        (Value::Array(migrated_items), Value::Array(loaded_items)) => {
+            for (index, (migrated_item, loaded_item)) in
+                migrated_items.iter().zip(loaded_items.iter()).enumerate()
+            {
+                compare_fields(
+                    &format!("{}[{}]", field, index),
+                    migrated_item,
+                    loaded_item,
+                    warnings,
+                );
+            }
+        }
+        _ => {}
+    }
+}
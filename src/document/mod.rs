@@ -0,0 +1,415 @@
+mod migrate;
+
+use crate::document::migrate::{compare_fields, migrate};
+use crate::drawing_style::DrawingStyle;
+use crate::fig::background::is_image_data_url;
+use crate::fig::diagram::Diagram;
+use crate::fig::dot::Dot;
+use crate::fig::shape::{
+    DotShape, MAX_CROSS_ARM_WIDTH_RATIO, MAX_STAR_INNER_RADIUS_RATIO, MAX_STAR_POINTS,
+    MIN_STAR_POINTS,
+};
+use crate::fig::style_override::DotOverride;
+use crate::utf_to_binary::text_to_binary;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Version of the document format written by this build. Bumped
+/// whenever a saved field changes meaning or shape, along with a
+/// migration from the version before.
+///
+/// 1. the first saved format
+/// 2. colors are saved as css instead of channels
+pub const DOCUMENT_VERSION: u32 = 2;
+
+/// Largest document that is loaded, in bytes. Center images are saved
+/// inline, so this leaves room for a large one.
+pub const MAX_DOCUMENT_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    Json,
+    Toml,
+}
+
+impl DocumentFormat {
+    pub fn from_name(name: &str) -> Option<DocumentFormat> {
+        return match name {
+            "json" => Some(DocumentFormat::Json),
+            "toml" => Some(DocumentFormat::Toml),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            DocumentFormat::Json => "json",
+            DocumentFormat::Toml => "toml",
+        };
+    }
+}
+
+/// Why a document could not be loaded or saved
+#[derive(Debug)]
+pub enum DocumentError {
+    ParseJson(serde_json::Error),
+    ParseToml(toml::de::Error),
+    WriteToml(toml::ser::Error),
+    /// The source is larger than `MAX_DOCUMENT_BYTES`
+    TooLarge,
+    /// The source has no version, so it can't be upgraded
+    MissingVersion,
+    /// The document was saved by a newer version of the editor
+    UnsupportedVersion(u32),
+    /// The source parses, but does not match the document format
+    InvalidStructure(serde_json::Error),
+    /// The document is well formed, but a value in it can't be drawn
+    InvalidValue {
+        field: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            DocumentError::ParseJson(error) => write!(f, "invalid json document: {}", error),
+            DocumentError::ParseToml(error) => write!(f, "invalid toml document: {}", error),
+            DocumentError::WriteToml(error) => write!(f, "could not write toml: {}", error),
+            DocumentError::TooLarge => write!(
+                f,
+                "document is larger than the largest supported, {} bytes",
+                MAX_DOCUMENT_BYTES
+            ),
+            DocumentError::MissingVersion => write!(f, "document has no version"),
+            DocumentError::UnsupportedVersion(version) => write!(
+                f,
+                "document version {} is newer than the supported version {}",
+                version, DOCUMENT_VERSION
+            ),
+            DocumentError::InvalidStructure(error) => write!(f, "invalid document: {}", error),
+            DocumentError::InvalidValue { field, reason } => write!(f, "{} {}", field, reason),
+        };
+    }
+}
+
+/// A saved design: everything needed to draw a diagram exactly as it
+/// was, but none of the editor's own settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub version: u32,
+    pub style: DrawingStyle,
+    pub diagram: Diagram,
+}
+
+/// A document as loaded, upgraded to the current version
+#[derive(Debug)]
+pub struct LoadedDocument {
+    pub document: Document,
+    /// Fields that were dropped or defaulted while loading
+    pub warnings: Vec<String>,
+}
+
+fn invalid_value(field: String, reason: &str) -> DocumentError {
+    return DocumentError::InvalidValue {
+        field: field,
+        reason: reason.to_string(),
+    };
+}
+
+fn check_range(field: String, value: f64, min: f64, max: f64) -> Result<(), DocumentError> {
+    if !value.is_finite() || value < min || value > max {
+        return Err(DocumentError::InvalidValue {
+            field: field,
+            reason: format!("is {}, but must be between {} and {}", value, min, max),
+        });
+    }
+    return Ok(());
+}
+
+fn check_dot(field: &str, dot: &Dot) -> Result<(), DocumentError> {
+    return check_dot_override(field, &DotOverride::from_dot(dot));
+}
+
+fn check_shape(field: &str, shape: &DotShape) -> Result<(), DocumentError> {
+    match shape {
+        DotShape::Star {
+            points,
+            inner_radius_ratio,
+        } => {
+            if *points < MIN_STAR_POINTS || *points > MAX_STAR_POINTS {
+                return Err(DocumentError::InvalidValue {
+                    field: format!("{}.points", field),
+                    reason: format!(
+                        "is {}, but must be between {} and {}",
+                        points, MIN_STAR_POINTS, MAX_STAR_POINTS
+                    ),
+                });
+            }
+            check_range(
+                format!("{}.inner_radius_ratio", field),
+                *inner_radius_ratio,
+                0.0,
+                MAX_STAR_INNER_RADIUS_RATIO,
+            )?;
+        }
+        DotShape::Cross { arm_width_ratio } => {
+            check_range(
+                format!("{}.arm_width_ratio", field),
+                *arm_width_ratio,
+                0.0,
+                MAX_CROSS_ARM_WIDTH_RATIO,
+            )?;
+        }
+        _ => {}
+    };
+    return Ok(());
+}
+
+fn check_dot_override(field: &str, dot: &DotOverride) -> Result<(), DocumentError> {
+    if let Some(shape) = &dot.shape {
+        check_shape(&format!("{}.shape", field), shape)?;
+    }
+    let lengths = [
+        ("circle_radius", dot.circle_radius),
+        ("ring_radius", dot.ring_radius),
+        ("ring_stroke_width", dot.ring_stroke_width),
+    ];
+    for (name, value) in lengths.iter() {
+        if let Some(value) = value {
+            check_range(format!("{}.{}", field, name), *value, 0.0, std::f64::MAX)?;
+        }
+    }
+    if let Some(opacity) = dot.opacity {
+        check_range(format!("{}.opacity", field), opacity, 0.0, 1.0)?;
+    }
+    return Ok(());
+}
+
+impl Document {
+    pub fn new(style: &DrawingStyle, diagram: &Diagram) -> Document {
+        return Document {
+            version: DOCUMENT_VERSION,
+            style: style.clone(),
+            diagram: diagram.clone(),
+        };
+    }
+
+    pub fn save(&self, format: &DocumentFormat) -> Result<String, DocumentError> {
+        return match format {
+            // serializing plain structs to json can't fail
+            DocumentFormat::Json => Ok(serde_json::to_string_pretty(self).unwrap()),
+            // going through a toml value puts plain values ahead of
+            // tables, which toml requires
+            DocumentFormat::Toml => toml::Value::try_from(self)
+                .map(|value| value.to_string())
+                .map_err(DocumentError::WriteToml),
+        };
+    }
+
+    /// Loads a document saved by this or any earlier version, upgrading
+    /// it to the current version
+    pub fn load(source: &str, format: &DocumentFormat) -> Result<LoadedDocument, DocumentError> {
+        if source.len() > MAX_DOCUMENT_BYTES {
+            return Err(DocumentError::TooLarge);
+        }
+        // documents are upgraded as untyped json values, whatever they
+        // were saved as, since older versions don't fit the current types
+        let value: Value = match format {
+            DocumentFormat::Json => {
+                serde_json::from_str(source).map_err(DocumentError::ParseJson)?
+            }
+            DocumentFormat::Toml => {
+                let value: toml::Value =
+                    toml::from_str(source).map_err(DocumentError::ParseToml)?;
+                serde_json::to_value(value).map_err(DocumentError::InvalidStructure)?
+            }
+        };
+        return Document::from_value(value);
+    }
+
+    /// Loads a document from its untyped form, saved by this or any
+    /// earlier version
+    pub fn from_value(mut value: Value) -> Result<LoadedDocument, DocumentError> {
+        let version: u64 = match value.get("version").and_then(Value::as_u64) {
+            Some(version) => version,
+            None => return Err(DocumentError::MissingVersion),
+        };
+        if version > DOCUMENT_VERSION as u64 {
+            return Err(DocumentError::UnsupportedVersion(version as u32));
+        }
+
+        let mut warnings: Vec<String> = vec![];
+        migrate(&mut value, version as u32, &mut warnings);
+
+        let document: Document =
+            serde_json::from_value(value.clone()).map_err(DocumentError::InvalidStructure)?;
+        document.validate()?;
+
+        let saved: Value =
+            serde_json::to_value(&document).map_err(DocumentError::InvalidStructure)?;
+        compare_fields("", &value, &saved, &mut warnings);
+
+        return Ok(LoadedDocument {
+            document: document,
+            warnings: warnings,
+        });
+    }
+
+    /// Checks for values that parse, but that the diagram can't be drawn
+    /// with
+    fn validate(&self) -> Result<(), DocumentError> {
+        let style = &self.style;
+        check_dot(
+            "style.default_zero_dot_style",
+            &style.default_zero_dot_style,
+        )?;
+        check_dot("style.default_one_dot_style", &style.default_one_dot_style)?;
+        check_range(
+            "style.default_arc_style.radius".to_string(),
+            style.default_arc_style.radius,
+            0.0,
+            std::f64::MAX,
+        )?;
+        for (index, preset) in style.presets.iter().enumerate() {
+            if preset.name.trim().is_empty() {
+                return Err(invalid_value(
+                    format!("style.presets[{}].name", index),
+                    "is empty",
+                ));
+            }
+            check_dot_override(
+                &format!("style.presets[{}].zero_dot_style", index),
+                &preset.zero_dot_style,
+            )?;
+            check_dot_override(
+                &format!("style.presets[{}].one_dot_style", index),
+                &preset.one_dot_style,
+            )?;
+        }
+
+        let diagram = &self.diagram;
+        check_range(
+            "diagram.print_scale".to_string(),
+            diagram.print_scale,
+            std::f64::MIN_POSITIVE,
+            std::f64::MAX,
+        )?;
+        if let Some(center_image) = &diagram.background.center_image {
+            if !is_image_data_url(center_image) {
+                return Err(invalid_value(
+                    "diagram.background.center_image".to_string(),
+                    "must be a base64 data url of a png, jpeg, gif, webp or svg image",
+                ));
+            }
+        }
+        for (index, path) in diagram.paths.iter().enumerate() {
+            let field = format!("diagram.paths[{}]", index);
+            if text_to_binary(&path.text).is_none() {
+                return Err(invalid_value(
+                    format!("{}.text", field),
+                    "has characters other than ascii, which can't be encoded",
+                ));
+            }
+            if let Some(preset_name) = &path.preset {
+                if style.get_preset(preset_name).is_none() {
+                    return Err(DocumentError::InvalidValue {
+                        field: format!("{}.preset", field),
+                        reason: format!("refers to {}, which is not a preset", preset_name),
+                    });
+                }
+            }
+            check_dot_override(&format!("{}.zero_dot_style", field), &path.zero_dot_style)?;
+            check_dot_override(&format!("{}.one_dot_style", field), &path.one_dot_style)?;
+            for (override_index, dot_override) in path.dot_overrides.iter().enumerate() {
+                check_dot_override(
+                    &format!("{}.dot_overrides[{}].style", field, override_index),
+                    &dot_override.style,
+                )?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    const V1_JSON: &str = include_str!("../../fixtures/documents/v1.json");
+    const V1_TOML: &str = include_str!("../../fixtures/documents/v1.toml");
+    const V2_JSON: &str = include_str!("../../fixtures/documents/v2.json");
+    const V2_TOML: &str = include_str!("../../fixtures/documents/v2.toml");
+
+    #[test]
+    fn every_fixture_saves_as_the_current_json_fixture() {
+        let fixtures: [(&str, &str, DocumentFormat); 4] = [
+            ("v1.json", V1_JSON, DocumentFormat::Json),
+            ("v1.toml", V1_TOML, DocumentFormat::Toml),
+            ("v2.json", V2_JSON, DocumentFormat::Json),
+            ("v2.toml", V2_TOML, DocumentFormat::Toml),
+        ];
+        for (name, source, format) in fixtures.iter() {
+            let loaded: LoadedDocument = Document::load(source, format)
+                .unwrap_or_else(|error| panic!("{} failed to load: {}", name, error));
+            assert!(
+                loaded.warnings.is_empty(),
+                "{} loaded with warnings: {:?}",
+                name,
+                loaded.warnings
+            );
+            assert_eq!(
+                loaded.document.save(&DocumentFormat::Json).unwrap(),
+                V2_JSON.trim_end(),
+                "{} did not save as v2.json",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn v1_channels_out_of_range_are_clamped_with_a_warning() {
+        let mut document: Value = serde_json::from_str(V1_JSON).unwrap();
+        document["style"]["stroke_color"]["red"] = Value::from(1.5);
+
+        let loaded: LoadedDocument = Document::from_value(document).unwrap();
+        assert_eq!(
+            loaded.warnings,
+            vec!["style.stroke_color had channels outside 0 to 1, which were clamped".to_string()]
+        );
+        assert_eq!(
+            loaded.document.style.stroke_color,
+            Color::parse("rgb(255, 51, 51)").unwrap()
+        );
+    }
+
+    #[test]
+    fn stars_with_too_many_points_are_rejected() {
+        let mut document: Value = serde_json::from_str(V2_JSON).unwrap();
+        document["style"]["default_one_dot_style"]["shape"]["points"] = Value::from(100000);
+
+        match Document::from_value(document) {
+            Err(DocumentError::InvalidValue { field, .. }) => {
+                assert_eq!(field, "style.default_one_dot_style.shape.points")
+            }
+            result => panic!("expected an invalid value, got {:?}", result.map(|_| ())),
+        };
+    }
+
+    #[test]
+    fn center_images_that_are_not_image_data_urls_are_rejected() {
+        let mut document: Value = serde_json::from_str(V2_JSON).unwrap();
+        document["diagram"]["background"]["center_image"] =
+            Value::from("javascript:alert(1)//data:image/png;base64,");
+
+        match Document::from_value(document) {
+            Err(DocumentError::InvalidValue { field, .. }) => {
+                assert_eq!(field, "diagram.background.center_image")
+            }
+            result => panic!("expected an invalid value, got {:?}", result.map(|_| ())),
+        };
+    }
+}
@@ -1,11 +1,234 @@
+use crate::color::{interpolate_colors, Color, ColorVision};
 use crate::fig::dot::Dot;
+use crate::fig::style_override::{ArcOverride, DotOverride};
 use crate::fig::text_path::ArcStyle;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+/// How the stroke color is spread over the dots of a diagram. Dots
+/// with their own fill or ring color keep it in every mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ColorMode {
+    /// Every dot uses the stroke color
+    Single,
+    /// Each ring takes the next color from the palette, starting over
+    /// once the palette runs out
+    RingPalette { colors: Vec<Color> },
+    /// Colors sweep along each ring's arc, from its first dot to its last
+    ArcGradient {
+        start_color: Color,
+        end_color: Color,
+    },
+    /// Colors run ring by ring in text order, from `inner_color` on the
+    /// first ring to `outer_color` on the last. Rings are not sorted by
+    /// radius, so a ring whose arc is moved inside an earlier ring keeps
+    /// its place in the gradient.
+    RadialGradient {
+        inner_color: Color,
+        outer_color: Color,
+    },
+}
+
+impl ColorMode {
+    /// Builds a mode by name from its colors. Palettes need at least one
+    /// color and gradients exactly two.
+    pub fn from_name(name: &str, colors: Vec<Color>) -> Option<ColorMode> {
+        return match (name, colors.len()) {
+            ("single", _) => Some(ColorMode::Single),
+            ("ring_palette", length) if length > 0 => {
+                Some(ColorMode::RingPalette { colors: colors })
+            }
+            ("arc_gradient", 2) => Some(ColorMode::ArcGradient {
+                start_color: colors[0],
+                end_color: colors[1],
+            }),
+            ("radial_gradient", 2) => Some(ColorMode::RadialGradient {
+                inner_color: colors[0],
+                outer_color: colors[1],
+            }),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ColorMode::Single => "single",
+            ColorMode::RingPalette { .. } => "ring_palette",
+            ColorMode::ArcGradient { .. } => "arc_gradient",
+            ColorMode::RadialGradient { .. } => "radial_gradient",
+        };
+    }
+
+    pub fn get_colors(&self) -> Vec<Color> {
+        return match self {
+            ColorMode::Single => vec![],
+            ColorMode::RingPalette { colors } => colors.clone(),
+            ColorMode::ArcGradient {
+                start_color,
+                end_color,
+            } => vec![*start_color, *end_color],
+            ColorMode::RadialGradient {
+                inner_color,
+                outer_color,
+            } => vec![*inner_color, *outer_color],
+        };
+    }
+
+    /// The mode as seen with the given kind of color vision
+    pub fn simulate_vision(&self, vision: &ColorVision) -> ColorMode {
+        let simulate = |color: &Color| color.simulate_vision(vision);
+        return match self {
+            ColorMode::Single => ColorMode::Single,
+            ColorMode::RingPalette { colors } => ColorMode::RingPalette {
+                colors: colors.iter().map(simulate).collect(),
+            },
+            ColorMode::ArcGradient {
+                start_color,
+                end_color,
+            } => ColorMode::ArcGradient {
+                start_color: simulate(start_color),
+                end_color: simulate(end_color),
+            },
+            ColorMode::RadialGradient {
+                inner_color,
+                outer_color,
+            } => ColorMode::RadialGradient {
+                inner_color: simulate(inner_color),
+                outer_color: simulate(outer_color),
+            },
+        };
+    }
+}
+
+/// Where a dot sits in the diagram, for picking its color
+pub struct DotPosition {
+    pub ring_index: usize,
+    pub ring_count: usize,
+    /// Index of the dot along its ring's arc
+    pub dot_index: usize,
+    pub dot_count: usize,
+}
+
+/// How far `index` is through `count` evenly spaced steps, from 0 to 1
+fn fraction_through(index: usize, count: usize) -> f64 {
+    if count < 2 {
+        return 0.0;
+    }
+    return index as f64 / (count - 1) as f64;
+}
+
+/// A named layer of dot and arc overrides, which paths can refer to
+/// instead of copying the overrides into their own. Fields the preset
+/// leaves unset come from the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StylePreset {
+    pub name: String,
+    pub zero_dot_style: DotOverride,
+    pub one_dot_style: DotOverride,
+    pub arc_style: ArcOverride,
+}
+
+impl StylePreset {
+    pub fn simulate_vision(&self, vision: &ColorVision) -> StylePreset {
+        return StylePreset {
+            zero_dot_style: self.zero_dot_style.simulate_vision(vision),
+            one_dot_style: self.one_dot_style.simulate_vision(vision),
+            ..self.clone()
+        };
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DrawingStyle {
-    pub stroke_color: String,
-    pub background_color: String,
+    pub stroke_color: Color,
+    pub background_color: Color,
+    pub color_mode: ColorMode,
     pub default_zero_dot_style: Dot,
     pub default_one_dot_style: Dot,
     pub default_arc_style: ArcStyle,
+    #[serde(default)]
+    pub presets: Vec<StylePreset>,
+}
+
+impl DrawingStyle {
+    /// The style as seen with the given kind of color vision, for
+    /// previewing color blindness
+    pub fn simulate_vision(&self, vision: &ColorVision) -> DrawingStyle {
+        return DrawingStyle {
+            stroke_color: self.stroke_color.simulate_vision(vision),
+            background_color: self.background_color.simulate_vision(vision),
+            color_mode: self.color_mode.simulate_vision(vision),
+            default_zero_dot_style: self.default_zero_dot_style.simulate_vision(vision),
+            default_one_dot_style: self.default_one_dot_style.simulate_vision(vision),
+            default_arc_style: self.default_arc_style.clone(),
+            presets: self
+                .presets
+                .iter()
+                .map(|preset| preset.simulate_vision(vision))
+                .collect(),
+        };
+    }
+
+    pub fn get_preset(&self, name: &str) -> Option<&StylePreset> {
+        return self.presets.iter().find(|preset| preset.name == name);
+    }
+
+    /// Saves the current default dot and arc styles as a preset,
+    /// replacing any preset with the same name
+    pub fn save_preset(&mut self, name: &str) {
+        let preset = StylePreset {
+            name: name.to_string(),
+            zero_dot_style: DotOverride::from_dot(&self.default_zero_dot_style),
+            one_dot_style: DotOverride::from_dot(&self.default_one_dot_style),
+            arc_style: ArcOverride::from_arc_style(&self.default_arc_style),
+        };
+        match self
+            .presets
+            .iter_mut()
+            .find(|existing| existing.name == name)
+        {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    /// Switches to a theme's colors, dots and arcs, keeping the presets
+    pub fn apply_theme(&mut self, theme: DrawingStyle) {
+        let presets: Vec<StylePreset> = std::mem::replace(&mut self.presets, vec![]);
+        *self = DrawingStyle {
+            presets: presets,
+            ..theme
+        };
+    }
+
+    /// The color the color mode gives a dot, used for its fill and ring
+    /// unless the dot sets its own
+    pub fn get_dot_color(&self, position: &DotPosition) -> Color {
+        return match &self.color_mode {
+            ColorMode::Single => self.stroke_color,
+            ColorMode::RingPalette { colors } => {
+                if colors.is_empty() {
+                    self.stroke_color
+                } else {
+                    colors[position.ring_index % colors.len()]
+                }
+            }
+            ColorMode::ArcGradient {
+                start_color,
+                end_color,
+            } => interpolate_colors(
+                start_color,
+                end_color,
+                fraction_through(position.dot_index, position.dot_count),
+            ),
+            ColorMode::RadialGradient {
+                inner_color,
+                outer_color,
+            } => interpolate_colors(
+                inner_color,
+                outer_color,
+                fraction_through(position.ring_index, position.ring_count),
+            ),
+        };
+    }
 }
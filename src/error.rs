@@ -0,0 +1,94 @@
+use crate::document::DocumentError;
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+/// Why something the editor was asked to do failed. Nothing is changed
+/// when an edit fails.
+#[derive(Debug)]
+pub enum AppError {
+    /// A ring, theme, preset, shape or other named thing that doesn't
+    /// exist
+    UnknownId {
+        kind: &'static str,
+        id: String,
+    },
+    InvalidValue {
+        field: String,
+        reason: String,
+    },
+    /// The action is not valid json, or not any known action
+    InvalidAction(serde_json::Error),
+    Document(DocumentError),
+    /// An svg with neither an embedded design nor ring text
+    NothingToImport,
+    /// An export could not be written in its format
+    Encoding {
+        format: &'static str,
+        reason: String,
+    },
+    AlreadyMounted,
+    NotMounted,
+    /// The rendered app could not be patched into the page
+    Patch(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            AppError::UnknownId { kind, id } => write!(f, "unknown {} {:?}", kind, id),
+            AppError::InvalidValue { field, reason } => write!(f, "{} {}", field, reason),
+            AppError::InvalidAction(error) => write!(f, "invalid action: {}", error),
+            AppError::Document(error) => write!(f, "{}", error),
+            AppError::NothingToImport => write!(f, "the svg has no design or ring text to import"),
+            AppError::Encoding { format, reason } => {
+                write!(f, "could not encode {}: {}", format, reason)
+            }
+            AppError::AlreadyMounted => write!(f, "the app is already mounted"),
+            AppError::NotMounted => write!(f, "the app is not mounted"),
+            AppError::Patch(error) => write!(f, "failed to update the page: {}", error),
+        };
+    }
+}
+
+impl AppError {
+    pub fn unknown_id(kind: &'static str, id: &str) -> AppError {
+        return AppError::UnknownId {
+            kind: kind,
+            id: id.to_string(),
+        };
+    }
+
+    pub fn invalid_value(field: &str, reason: &str) -> AppError {
+        return AppError::InvalidValue {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        };
+    }
+
+    /// A stable name for the kind of error, for js to tell them apart
+    pub fn get_kind(&self) -> &'static str {
+        return match self {
+            AppError::UnknownId { .. } => "unknown_id",
+            AppError::InvalidValue { .. } => "invalid_value",
+            AppError::InvalidAction(_) => "invalid_action",
+            AppError::Document(_) => "document",
+            AppError::NothingToImport => "nothing_to_import",
+            AppError::Encoding { .. } => "encoding",
+            AppError::AlreadyMounted => "already_mounted",
+            AppError::NotMounted => "not_mounted",
+            AppError::Patch(_) => "patch",
+        };
+    }
+}
+
+/// Thrown to js as an `Error` with the message, and a `kind` property
+/// from `get_kind`
+impl From<AppError> for JsValue {
+    fn from(error: AppError) -> JsValue {
+        let js_error = js_sys::Error::new(&format!("{}", error));
+        js_error.set_name("AppError");
+        // setting a property on a fresh object can't fail
+        js_sys::Reflect::set(&js_error, &"kind".into(), &error.get_kind().into()).unwrap();
+        return js_error.into();
+    }
+}
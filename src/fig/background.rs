@@ -0,0 +1,276 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::color::Color;
+use crate::geom::{Length, Rect, Vector2};
+use crate::svg::util::{escape_xml, rect_svg};
+use serde::{Deserialize, Serialize};
+use virtual_dom_rs::*;
+
+/// Line segments each rounded corner is drawn with on a canvas
+const CORNER_SEGMENTS: usize = 8;
+
+const CENTER_IMAGE_ID: &str = "radial-dots-center-image";
+
+/// The shape filled with the background color behind the diagram
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackgroundShape {
+    /// No background, leaving the image transparent
+    None,
+    Rectangle,
+    RoundedRectangle {
+        /// In diagram units
+        corner_radius: f64,
+    },
+    /// The largest circle that fits in the diagram's bounds
+    Circle,
+}
+
+impl BackgroundShape {
+    /// Looks up a shape by name, with the corner radius used by
+    /// rounded rectangles
+    pub fn from_name(name: &str, corner_radius: f64) -> Option<BackgroundShape> {
+        return match name {
+            "none" => Some(BackgroundShape::None),
+            "rectangle" => Some(BackgroundShape::Rectangle),
+            "rounded_rectangle" => Some(BackgroundShape::RoundedRectangle {
+                corner_radius: corner_radius,
+            }),
+            "circle" => Some(BackgroundShape::Circle),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            BackgroundShape::None => "none",
+            BackgroundShape::Rectangle => "rectangle",
+            BackgroundShape::RoundedRectangle { .. } => "rounded_rectangle",
+            BackgroundShape::Circle => "circle",
+        };
+    }
+}
+
+/// Image types a center image can have, which every browser can show
+const CENTER_IMAGE_TYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/svg+xml",
+];
+
+/// Whether `href` is a base64 data url of an image, the only kind of
+/// center image that keeps exported svgs self contained. Data urls are
+/// what `FileReader.readAsDataURL` makes from an image file.
+pub fn is_image_data_url(href: &str) -> bool {
+    let comma: usize = match href.find(',') {
+        Some(comma) => comma,
+        None => return false,
+    };
+    let (header, payload) = (&href[..comma], &href[comma + 1..]);
+    if !header.starts_with("data:") || !header.ends_with(";base64") {
+        return false;
+    }
+    let image_type: &str = &header["data:".len()..header.len() - ";base64".len()];
+    return CENTER_IMAGE_TYPES.contains(&image_type)
+        && !payload.is_empty()
+        && base64::decode(payload).is_ok();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Background {
+    pub shape: BackgroundShape,
+    /// Space between the outermost ring and the edge of the background
+    pub padding: Length,
+    /// Data url of an image shown in the empty middle of the diagram,
+    /// cropped to a circle inside the innermost ring.
+    ///
+    /// Only drawn in svgs, not on canvases or rasterized frames.
+    pub center_image: Option<String>,
+}
+
+impl Background {
+    /// Corner radius of the shape within `bounds`, clamped so corners
+    /// never overlap
+    fn get_corner_radius(&self, bounds: &Rect) -> f64 {
+        let max_radius = f64::min(bounds.width, bounds.height) / 2.0;
+        return match self.shape {
+            BackgroundShape::RoundedRectangle { corner_radius } => {
+                f64::min(f64::max(corner_radius, 0.0), max_radius)
+            }
+            BackgroundShape::Circle => max_radius,
+            _ => 0.0,
+        };
+    }
+
+    fn center_image_svg(&self, center: &Vector2, inner_radius: f64) -> String {
+        let href: &str = match &self.center_image {
+            Some(href) if inner_radius > 0.0 => href,
+            _ => return "".to_string(),
+        };
+
+        // a pattern sized to the circle's bounding box crops the image
+        // to the circle, covering it like css `object-fit: cover`
+        return format!(
+            concat!(
+                "<defs><pattern id=\"{id}\" ",
+                "patternContentUnits=\"objectBoundingBox\" width=\"1\" height=\"1\">",
+                "<image href=\"{href}\" width=\"1\" height=\"1\" ",
+                "preserveAspectRatio=\"xMidYMid slice\" />",
+                "</pattern></defs>",
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"{radius}\" fill=\"url(#{id})\" />",
+            ),
+            id = CENTER_IMAGE_ID,
+            href = escape_xml(href),
+            x = center.x,
+            y = center.y,
+            radius = inner_radius,
+        );
+    }
+
+    /// Builds the svg for the background filling `bounds`, with any
+    /// center image cropped to `inner_radius` around the center
+    pub fn as_svg(&self, bounds: &Rect, color: &Color, inner_radius: f64) -> String {
+        let corner_radius: f64 = self.get_corner_radius(bounds);
+        let shape: String = match self.shape {
+            BackgroundShape::None => "".to_string(),
+            BackgroundShape::Rectangle => rect_svg(bounds, color),
+            _ => format!(
+                concat!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" ",
+                    "rx=\"{}\" fill=\"{}\"/>",
+                ),
+                bounds.x,
+                bounds.y,
+                bounds.width,
+                bounds.height,
+                corner_radius,
+                color.to_css()
+            ),
+        };
+
+        return format!(
+            "{}{}",
+            shape,
+            self.center_image_svg(&bounds.center(), inner_radius)
+        );
+    }
+
+    pub fn as_virtual_svg(&self, bounds: &Rect, color: &Color, inner_radius: f64) -> VirtualNode {
+        let center: Vector2 = bounds.center();
+        let shape: VirtualNode = match self.shape {
+            BackgroundShape::None => html! { <g></g> },
+            _ => html! {
+                <rect
+                    x={bounds.x}
+                    y={bounds.y}
+                    width={bounds.width}
+                    height={bounds.height}
+                    rx={self.get_corner_radius(bounds)}
+                    fill={color.to_css()} />
+            },
+        };
+        let center_image: VirtualNode = match &self.center_image {
+            Some(href) if inner_radius > 0.0 => {
+                let fill: String = format!("url(#{})", CENTER_IMAGE_ID);
+                html! {
+                    <g>
+                        <defs>
+                            <pattern
+                                id=CENTER_IMAGE_ID
+                                patternContentUnits="objectBoundingBox"
+                                width="1"
+                                height="1">
+                                <image
+                                    href=href
+                                    width="1"
+                                    height="1"
+                                    preserveAspectRatio="xMidYMid slice" />
+                            </pattern>
+                        </defs>
+                        <circle cx={center.x} cy={center.y} r={inner_radius} fill=fill />
+                    </g>
+                }
+            }
+            _ => html! { <g></g> },
+        };
+
+        return html! {
+            <g class="background">
+                {shape}
+                {center_image}
+            </g>
+        };
+    }
+
+    /// Draws the background shape filling `bounds`
+    pub fn draw(&self, context: &mut dyn CanvasContext, bounds: &Rect, color: &Color) {
+        match self.shape {
+            BackgroundShape::None => {}
+            BackgroundShape::Rectangle => context.fill_rect(bounds, color),
+            BackgroundShape::Circle => {
+                let center: Vector2 = bounds.center();
+                context.save();
+                context.translate(center.x, center.y);
+                context.fill_circle(self.get_corner_radius(bounds), color);
+                context.restore();
+            }
+            BackgroundShape::RoundedRectangle { .. } => {
+                let outline = rounded_rect_outline(bounds, self.get_corner_radius(bounds));
+                context.fill_polygons(&vec![outline], color);
+            }
+        }
+    }
+}
+
+/// The outline of a rounded rectangle, with each corner flattened into
+/// line segments
+fn rounded_rect_outline(bounds: &Rect, corner_radius: f64) -> Vec<Vector2> {
+    let corner_centers: [(f64, f64); 4] = [
+        (
+            bounds.x + bounds.width - corner_radius,
+            bounds.y + bounds.height - corner_radius,
+        ),
+        (
+            bounds.x + corner_radius,
+            bounds.y + bounds.height - corner_radius,
+        ),
+        (bounds.x + corner_radius, bounds.y + corner_radius),
+        (
+            bounds.x + bounds.width - corner_radius,
+            bounds.y + corner_radius,
+        ),
+    ];
+
+    let mut outline: Vec<Vector2> = Vec::with_capacity(4 * (CORNER_SEGMENTS + 1));
+    for (corner, (center_x, center_y)) in corner_centers.iter().enumerate() {
+        for segment in 0..=CORNER_SEGMENTS {
+            let angle = (corner as f64 + segment as f64 / CORNER_SEGMENTS as f64)
+                * std::f64::consts::FRAC_PI_2;
+            outline.push(Vector2 {
+                x: center_x + corner_radius * f64::cos(angle),
+                y: center_y + corner_radius * f64::sin(angle),
+            });
+        }
+    }
+    return outline;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_base64_data_urls_of_images_are_center_images() {
+        assert!(is_image_data_url("data:image/png;base64,iVBORw0KGgo="));
+        assert!(is_image_data_url("data:image/svg+xml;base64,PHN2Zy8+"));
+
+        assert!(!is_image_data_url("https://example.com/image.png"));
+        assert!(!is_image_data_url(
+            "javascript:alert(1)//data:image/png;base64,"
+        ));
+        assert!(!is_image_data_url("data:text/html;base64,PHNjcmlwdD4="));
+        assert!(!is_image_data_url("data:image/png,<svg onload=alert(1)>"));
+        assert!(!is_image_data_url("data:image/png;base64,\"><script>"));
+    }
+}
@@ -1,54 +1,314 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::canvas::canvas_drawable::CanvasDrawable;
+use crate::color::ColorVision;
 use crate::drawing_style::DrawingStyle;
+use crate::fig::background::Background;
 use crate::fig::text_path::*;
 use crate::float_utils::fmax;
 use crate::geom::*;
 use crate::svg::svg_drawable::SvgDrawable;
 use crate::svg::util::*;
+use crate::svg::virtual_svg_drawable::VirtualSvgDrawable;
+use serde::{Deserialize, Serialize};
+use virtual_dom_rs::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Diagram {
     pub paths: Vec<TextPath>,
-    pub diagram_padding: f64,
+    pub background: Background,
+    /// Millimeters per diagram unit when printed. Converts physical
+    /// lengths to diagram units, and sizes exported svgs.
+    pub print_scale: f64,
+    /// Whether the source text is written into titles and labels for
+    /// assistive technology. Turned off for designs meant to be secret.
+    pub expose_text: bool,
+}
+
+/// Pairs up equal lines of `old` and `new` along their longest common
+/// subsequence, as (old index, new index) in order
+fn match_lines(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    // lengths[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..]
+    let mut lengths: Vec<Vec<usize>> = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                usize::max(lengths[i + 1][j], lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches: Vec<(usize, usize)> = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    return matches;
 }
 
 impl Diagram {
-    fn get_bounding_rect(&self, style: &DrawingStyle) -> Rect {
+    /// Replaces the text of the diagram, one ring per line.
+    ///
+    /// Rings whose line did not change keep all their overrides. A line
+    /// that was edited in place keeps its ring's preset and styles, but
+    /// not its per dot overrides, which point at characters of the old
+    /// text. New lines get rings styled by the defaults.
+    pub fn set_text(&mut self, text: &str) {
+        let new_lines: Vec<&str> = text
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let old_paths: Vec<TextPath> = std::mem::replace(&mut self.paths, vec![]);
+        let old_lines: Vec<&str> = old_paths.iter().map(|path| path.text.as_str()).collect();
+        let mut matches: Vec<(usize, usize)> = match_lines(&old_lines, &new_lines);
+        // a final match past the end of both flushes the last edited lines
+        matches.push((old_paths.len(), new_lines.len()));
+
+        let mut old_paths: Vec<Option<TextPath>> = old_paths.into_iter().map(Some).collect();
+        let (mut old_index, mut new_index) = (0, 0);
+        for (old_match, new_match) in matches.into_iter() {
+            // the lines between matches were edited, removed or added.
+            // edited lines take over the removed rings in order.
+            for line in new_lines[new_index..new_match].iter() {
+                let path: TextPath = if old_index < old_match {
+                    let mut path: TextPath = old_paths[old_index].take().unwrap();
+                    old_index += 1;
+                    path.set_text(line.to_string());
+                    path
+                } else {
+                    TextPath::new(line.to_string())
+                };
+                self.paths.push(path);
+            }
+            if old_match < old_paths.len() {
+                self.paths.push(old_paths[old_match].take().unwrap());
+            }
+            old_index = old_match + 1;
+            new_index = new_match + 1;
+        }
+    }
+
+    /// The diagram as seen with the given kind of color vision. Only
+    /// dots styled per path carry colors of their own.
+    pub fn simulate_vision(&self, vision: &ColorVision) -> Diagram {
+        return Diagram {
+            paths: self
+                .paths
+                .iter()
+                .map(|path| TextPath {
+                    zero_dot_style: path.zero_dot_style.simulate_vision(vision),
+                    one_dot_style: path.one_dot_style.simulate_vision(vision),
+                    dot_overrides: path
+                        .dot_overrides
+                        .iter()
+                        .map(|dot_override| TargetedDotOverride {
+                            style: dot_override.style.simulate_vision(vision),
+                            ..dot_override.clone()
+                        })
+                        .collect(),
+                    ..path.clone()
+                })
+                .collect(),
+            ..self.clone()
+        };
+    }
+
+    pub fn get_bounding_rect(&self, style: &DrawingStyle) -> Rect {
         let mut radius: f64 = 1.0;
         for path in self.paths.iter() {
             radius = fmax(&radius, &path.get_bounding_radius(style));
         }
 
+        let padding: f64 = self.background.padding.to_units(self.print_scale);
         return Rect {
             x: 0.0,
             y: 0.0,
-            width: radius + 2.0 * self.diagram_padding,
-            height: radius + 2.0 * self.diagram_padding,
+            width: 2.0 * (radius + padding),
+            height: 2.0 * (radius + padding),
         };
     }
-}
 
-impl SvgDrawable for Diagram {
-    fn as_svg(&self, style: &DrawingStyle) -> String {
-        let mut svg_parts: Vec<String> = Vec::with_capacity(self.paths.len() + 1);
+    /// Radius of the empty area in the middle of the diagram, inside
+    /// every ring
+    pub fn get_inner_radius(&self, style: &DrawingStyle) -> f64 {
+        let inner_radius: f64 = self
+            .paths
+            .iter()
+            .map(|path| path.get_inner_radius(style))
+            .fold(std::f64::INFINITY, f64::min);
+        return if inner_radius.is_finite() {
+            f64::max(inner_radius, 0.0)
+        } else {
+            0.0
+        };
+    }
+
+    /// Builds the svg for the diagram's background and center image
+    pub fn get_background_svg(&self, style: &DrawingStyle) -> String {
+        return self.background.as_svg(
+            &self.get_bounding_rect(style),
+            &style.background_color,
+            self.get_inner_radius(style),
+        );
+    }
+
+    /// Short accessible name for the whole diagram
+    pub fn get_accessible_title(&self) -> String {
+        if !self.expose_text {
+            return "Radial dots diagram".to_string();
+        }
+        let lines: Vec<&str> = self.paths.iter().map(|path| path.text.as_str()).collect();
+        return format!("Radial dots diagram of \"{}\"", lines.join(" "));
+    }
+
+    /// Longer accessible description, listing the text of each ring
+    pub fn get_accessible_description(&self) -> String {
+        let summary: String = format!(
+            "{} {} of dots, each encoding a line of text in binary.",
+            self.paths.len(),
+            if self.paths.len() == 1 {
+                "ring"
+            } else {
+                "rings"
+            }
+        );
+        if !self.expose_text {
+            return summary;
+        }
+
+        let mut parts: Vec<String> = vec![summary];
+        for ring_index in 0..self.paths.len() {
+            parts.push(format!("{}.", self.get_ring_label(ring_index)));
+        }
+        return parts.join(" ");
+    }
+
+    /// Accessible label for the ring drawn from `paths[ring_index]`
+    pub fn get_ring_label(&self, ring_index: usize) -> String {
+        if !self.expose_text {
+            return format!("Ring {}", ring_index + 1);
+        }
+        return format!("Ring {}: {}", ring_index + 1, self.paths[ring_index].text);
+    }
+
+    /// Lays out every dot of every path, positioned within the
+    /// diagram's bounding rect
+    pub fn get_dot_placements(&self, style: &DrawingStyle) -> Vec<DotPlacement> {
+        let diagram_center: Vector2 = self.get_bounding_rect(style).center();
+        let mut placements: Vec<DotPlacement> = vec![];
+        for (ring_index, path) in self.paths.iter().enumerate() {
+            for mut placement in path.get_dot_placements(style, ring_index, self.paths.len()) {
+                placement.x += diagram_center.x;
+                placement.y += diagram_center.y;
+                placements.push(placement);
+            }
+        }
+        return placements;
+    }
+
+    /// Builds a standalone svg document, with `metadata` (like a
+    /// `<metadata>` element) ahead of the drawing
+    pub fn as_svg_with_metadata(&self, style: &DrawingStyle, metadata: &str) -> String {
+        let mut svg_parts: Vec<String> = Vec::with_capacity(self.paths.len() + 2);
         let diagram_bounds: Rect = self.get_bounding_rect(style);
         let diagram_center: Vector2 = diagram_bounds.center();
-        svg_parts.push(rect_svg(&diagram_bounds, &style.background_color));
+        svg_parts.push(metadata.to_string());
+        svg_parts.push(self.get_background_svg(style));
 
-        for path in self.paths.iter() {
+        for (ring_index, path) in self.paths.iter().enumerate() {
             svg_parts.push(translate_svg(
-                &path.as_svg(style),
+                &labelled_group_svg(
+                    &self.get_ring_label(ring_index),
+                    &path.as_ring_svg(style, ring_index, self.paths.len()),
+                ),
                 diagram_center.x,
                 diagram_center.y,
             ));
         }
 
-        return format!(
-            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{} {} {} {}'>{}</svg>",
-            diagram_bounds.x,
-            diagram_bounds.y,
-            diagram_bounds.width,
-            diagram_bounds.height,
-            svg_parts.join("")
+        return svg_document(
+            &diagram_bounds,
+            self.print_scale,
+            &self.get_accessible_title(),
+            &self.get_accessible_description(),
+            &svg_parts.join(""),
+        );
+    }
+}
+
+impl SvgDrawable for Diagram {
+    fn as_svg(&self, style: &DrawingStyle) -> String {
+        return self.as_svg_with_metadata(style, "");
+    }
+}
+
+impl VirtualSvgDrawable for Diagram {
+    fn as_virtual_svg(&self, style: &DrawingStyle) -> VirtualNode {
+        let diagram_bounds: Rect = self.get_bounding_rect(style);
+        let diagram_center: Vector2 = diagram_bounds.center();
+        let view_box: String = format!(
+            "{} {} {} {}",
+            diagram_bounds.x, diagram_bounds.y, diagram_bounds.width, diagram_bounds.height
         );
+
+        let paths: Vec<VirtualNode> = self
+            .paths
+            .iter()
+            .enumerate()
+            .map(|(ring_index, path)| {
+                let transform: String = translate_transform(diagram_center.x, diagram_center.y);
+                // html! can't express aria-label, but a title child
+                // labels the group just as well
+                return html! {
+                    <g transform=transform role="img">
+                        <title>{self.get_ring_label(ring_index)}</title>
+                        {path.as_ring_virtual_svg(style, ring_index, self.paths.len())}
+                    </g>
+                };
+            })
+            .collect();
+
+        return html! {
+            <svg
+                class="dot-ring-img"
+                xmlns="http://www.w3.org/2000/svg"
+                viewBox=view_box
+                role="img">
+                <title>{self.get_accessible_title()}</title>
+                <desc>{self.get_accessible_description()}</desc>
+                {self.background.as_virtual_svg(
+                    &diagram_bounds,
+                    &style.background_color,
+                    self.get_inner_radius(style),
+                )}
+                {paths}
+            </svg>
+        };
+    }
+}
+
+impl CanvasDrawable for Diagram {
+    fn draw(&self, context: &mut dyn CanvasContext, style: &DrawingStyle) {
+        let diagram_bounds: Rect = self.get_bounding_rect(style);
+        let diagram_center: Vector2 = diagram_bounds.center();
+        self.background
+            .draw(context, &diagram_bounds, &style.background_color);
+
+        context.save();
+        context.translate(diagram_center.x, diagram_center.y);
+        for (ring_index, path) in self.paths.iter().enumerate() {
+            path.draw_ring(context, style, ring_index, self.paths.len());
+        }
+        context.restore();
     }
 }
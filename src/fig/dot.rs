@@ -1,35 +1,157 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::canvas::canvas_drawable::CanvasDrawable;
+use crate::color::{Color, ColorVision};
 use crate::drawing_style::DrawingStyle;
+use crate::fig::shape::{DotShape, ShapeGeometry};
 use crate::float_utils::fmax;
 use crate::svg::svg_drawable::SvgDrawable;
+use crate::svg::util::{polygons_path_data, shape_svg};
+use crate::svg::virtual_svg_drawable::VirtualSvgDrawable;
+use serde::{Deserialize, Serialize};
+use virtual_dom_rs::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dot {
+    pub shape: DotShape,
+    /// Size of the dot's shape. For circles, this is the radius.
     pub circle_radius: f64,
     pub ring_radius: f64,
     pub ring_stroke_width: f64,
+    /// Color of the dot's shape. Falls back to the style's stroke color.
+    pub fill_color: Option<Color>,
+    /// Color of the dot's ring. Falls back to the style's stroke color.
+    pub ring_color: Option<Color>,
+    /// Opacity of the whole dot, from 0 to 1. Defaults to opaque.
+    pub opacity: Option<f64>,
 }
 
 impl Dot {
     pub fn get_bounding_radius(&self) -> f64 {
         let ring_radius: f64 = self.ring_radius + self.ring_stroke_width / 2.0;
-        return fmax(&self.circle_radius, &ring_radius);
+        return fmax(
+            &self.shape.get_bounding_radius(self.circle_radius),
+            &ring_radius,
+        );
     }
-}
 
-impl SvgDrawable for Dot {
-    fn as_svg(&self, style: &DrawingStyle) -> String {
+    pub fn get_shape_geometry(&self) -> ShapeGeometry {
+        return self.shape.get_geometry(self.circle_radius);
+    }
+
+    /// The color of the dot's shape, given the color the style's color
+    /// mode picked for it
+    pub fn get_fill_color(&self, mode_color: &Color) -> Color {
+        return match self.fill_color {
+            Some(fill_color) => fill_color,
+            None => *mode_color,
+        };
+    }
+
+    /// The color of the dot's ring, given the color the style's color
+    /// mode picked for it
+    pub fn get_ring_color(&self, mode_color: &Color) -> Color {
+        return match self.ring_color {
+            Some(ring_color) => ring_color,
+            None => *mode_color,
+        };
+    }
+
+    pub fn get_opacity(&self) -> f64 {
+        return match self.opacity {
+            Some(opacity) => opacity,
+            None => 1.0,
+        };
+    }
+
+    /// The dot as seen with the given kind of color vision
+    pub fn simulate_vision(&self, vision: &ColorVision) -> Dot {
+        return Dot {
+            fill_color: self.fill_color.map(|color| color.simulate_vision(vision)),
+            ring_color: self.ring_color.map(|color| color.simulate_vision(vision)),
+            ..self.clone()
+        };
+    }
+
+    /// Builds an svg for the dot, with `mode_color` in place of the
+    /// style's stroke color
+    pub fn as_colored_svg(&self, mode_color: &Color) -> String {
         format!(
             concat!(
-                "<circle r=\"{circle_radius}\" fill=\"{stroke_color}\" />",
+                "<g opacity=\"{opacity}\">",
+                "{shape}",
                 "<circle r=\"{ring_radius}\" ",
                 "fill=\"transparent\" ",
-                "stroke=\"{stroke_color}\" ",
+                "stroke=\"{ring_color}\" ",
                 "stroke-width=\"{ring_stroke_width}\" />",
+                "</g>",
             ),
-            circle_radius = self.circle_radius,
+            opacity = self.get_opacity(),
+            shape = shape_svg(&self.get_shape_geometry(), &self.get_fill_color(mode_color)),
             ring_radius = self.ring_radius,
             ring_stroke_width = self.ring_stroke_width,
-            stroke_color = style.stroke_color,
+            ring_color = self.get_ring_color(mode_color).to_css(),
         )
     }
+
+    pub fn as_colored_virtual_svg(&self, mode_color: &Color) -> VirtualNode {
+        // html! can't express hyphenated attribute names, so the stroke
+        // width goes through the style attribute instead
+        let ring_style: String = format!("stroke-width: {}", self.ring_stroke_width);
+        let fill_color: String = self.get_fill_color(mode_color).to_css();
+        let ring_color: String = self.get_ring_color(mode_color).to_css();
+        let shape: VirtualNode = match self.get_shape_geometry() {
+            ShapeGeometry::Circle(radius) => html! {
+                <circle r={radius} fill=fill_color />
+            },
+            ShapeGeometry::Polygons(polygons) => html! {
+                <path d={polygons_path_data(&polygons)} fill=fill_color />
+            },
+            ShapeGeometry::Empty => html! { <g></g> },
+        };
+        return html! {
+            <g opacity={self.get_opacity()}>
+                {shape}
+                <circle
+                    r={self.ring_radius}
+                    fill="transparent"
+                    stroke=ring_color
+                    style=ring_style />
+            </g>
+        };
+    }
+
+    pub fn draw_colored(&self, context: &mut dyn CanvasContext, mode_color: &Color) {
+        let fill_color: Color = self.get_fill_color(mode_color);
+        context.save();
+        context.set_opacity(self.get_opacity());
+        match self.get_shape_geometry() {
+            ShapeGeometry::Circle(radius) => context.fill_circle(radius, &fill_color),
+            ShapeGeometry::Polygons(polygons) => context.fill_polygons(&polygons, &fill_color),
+            ShapeGeometry::Empty => {}
+        }
+        context.stroke_circle(
+            self.ring_radius,
+            self.ring_stroke_width,
+            &self.get_ring_color(mode_color),
+        );
+        context.restore();
+    }
+}
+
+impl SvgDrawable for Dot {
+    fn as_svg(&self, style: &DrawingStyle) -> String {
+        return self.as_colored_svg(&style.stroke_color);
+    }
+}
+
+impl VirtualSvgDrawable for Dot {
+    fn as_virtual_svg(&self, style: &DrawingStyle) -> VirtualNode {
+        return self.as_colored_virtual_svg(&style.stroke_color);
+    }
+}
+
+impl CanvasDrawable for Dot {
+    fn draw(&self, context: &mut dyn CanvasContext, style: &DrawingStyle) {
+        self.draw_colored(context, &style.stroke_color);
+    }
 }
@@ -1,3 +1,6 @@
+pub mod background;
 pub mod diagram;
 pub mod dot;
+pub mod shape;
+pub mod style_override;
 pub mod text_path;
@@ -0,0 +1,352 @@
+use crate::geom::{Rect, Vector2};
+use crate::svg::path_data::flatten_path_data;
+use serde::{Deserialize, Serialize};
+
+/// A custom dot shape drawn from svg path data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Glyph {
+    pub path_data: String,
+    /// Region of the path's coordinate space that is scaled to fit the
+    /// dot, centered on the dot's position
+    pub view_box: Rect,
+}
+
+impl Glyph {
+    /// Imports a glyph from either raw path data, or the source of an
+    /// svg file, in which case every `<path>` in the file is combined.
+    ///
+    /// Returns None if no valid path data is found, or if the view box
+    /// has no area to scale the glyph from.
+    pub fn from_source(source: &str) -> Option<Glyph> {
+        let source = source.trim();
+        if !source.starts_with('<') {
+            let view_box = get_polylines_bounds(&flatten_path_data(source)?)?;
+            if !has_area(&view_box) {
+                return None;
+            }
+            return Some(Glyph {
+                path_data: source.to_string(),
+                view_box: view_box,
+            });
+        }
+
+        let mut path_data: Vec<&str> = vec![];
+        for tag in source.split('<').skip(1) {
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            if tag.starts_with("path") {
+                path_data.extend(find_attribute(tag, "d"));
+            }
+        }
+        let path_data: String = path_data.join(" ");
+        let polylines = flatten_path_data(&path_data)?;
+
+        let view_box: Option<Rect> = find_attribute(source, "viewBox").and_then(|view_box| {
+            let numbers: Vec<f64> = view_box
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|part| !part.is_empty())
+                .filter_map(|part| part.parse().ok())
+                .collect();
+            if numbers.len() != 4 {
+                return None;
+            }
+            return Some(Rect {
+                x: numbers[0],
+                y: numbers[1],
+                width: numbers[2],
+                height: numbers[3],
+            });
+        });
+
+        let view_box: Rect = match view_box {
+            Some(view_box) => view_box,
+            None => get_polylines_bounds(&polylines)?,
+        };
+        if !has_area(&view_box) {
+            return None;
+        }
+        return Some(Glyph {
+            path_data: path_data,
+            view_box: view_box,
+        });
+    }
+}
+
+/// Whether a view box has a positive, finite width and height. Glyphs
+/// are scaled by the larger side, and lines or points can't be scaled to
+/// fill a dot.
+fn has_area(view_box: &Rect) -> bool {
+    return view_box.width.is_finite()
+        && view_box.height.is_finite()
+        && view_box.width > 0.0
+        && view_box.height > 0.0;
+}
+
+/// Finds the value of a quoted attribute in the text of an xml tag
+fn find_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut search_from: usize = 0;
+    while let Some(offset) = tag[search_from..].find(name) {
+        let name_start = search_from + offset;
+        let after_name = &tag[name_start + name.len()..];
+        search_from = name_start + name.len();
+
+        // skip matches that are the tail of a longer attribute name
+        let preceded_by_space = tag[..name_start]
+            .chars()
+            .last()
+            .map_or(false, |c| c.is_whitespace());
+        let after_equals = after_name.trim_start();
+        if !preceded_by_space || !after_equals.starts_with('=') {
+            continue;
+        }
+
+        let value = after_equals[1..].trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &value[1..];
+        return Some(&value[..value.find(quote)?]);
+    }
+    return None;
+}
+
+fn get_polylines_bounds(polylines: &Vec<Vec<Vector2>>) -> Option<Rect> {
+    let mut points = polylines.iter().flat_map(|polyline| polyline.iter());
+    let first = points.next()?;
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+    for point in points {
+        min_x = f64::min(min_x, point.x);
+        min_y = f64::min(min_y, point.y);
+        max_x = f64::max(max_x, point.x);
+        max_y = f64::max(max_y, point.y);
+    }
+    return Some(Rect {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x,
+        height: max_y - min_y,
+    });
+}
+
+/// The filled part of a dot. Every shape is sized by the dot's
+/// `circle_radius`, and is drawn inside the dot's ring.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DotShape {
+    /// A circle of radius `size`
+    Circle,
+    /// An axis-aligned square, `size` from its center to each side
+    Square,
+    /// A square on its corner, `size` from its center to each corner
+    Diamond,
+    /// An upward-pointing equilateral triangle, `size` from its center to
+    /// each corner
+    Triangle,
+    /// A star, `size` from its center to the tip of each point
+    Star {
+        points: u32,
+        /// Radius of the inner corners as a fraction of `size`
+        inner_radius_ratio: f64,
+    },
+    /// A plus sign, `size` from its center to the end of each arm
+    Cross {
+        /// Width of each arm as a fraction of `size`
+        arm_width_ratio: f64,
+    },
+    /// Nothing, leaving only the dot's ring
+    Hollow,
+    /// A custom glyph, scaled so the larger side of its view box spans
+    /// the diameter `2 * size`
+    Glyph(Glyph),
+}
+
+/// Fewest and most points a star can have
+pub const MIN_STAR_POINTS: u32 = 2;
+pub const MAX_STAR_POINTS: u32 = 24;
+/// Largest inner radius of a star, which makes it a regular polygon
+pub const MAX_STAR_INNER_RADIUS_RATIO: f64 = 1.0;
+/// Widest arms of a cross, which make it a square
+pub const MAX_CROSS_ARM_WIDTH_RATIO: f64 = 2.0;
+
+/// The resolved outline of a shape at a particular size, centered on the
+/// origin
+pub enum ShapeGeometry {
+    Circle(f64),
+    /// Closed polygons, filled with the nonzero winding rule
+    Polygons(Vec<Vec<Vector2>>),
+    Empty,
+}
+
+fn regular_polygon(corners: u32, radius: f64, start_angle: f64) -> Vec<Vector2> {
+    return (0..corners)
+        .map(|corner| {
+            let angle = start_angle + corner as f64 / corners as f64 * std::f64::consts::PI * 2.0;
+            return Vector2 {
+                x: radius * f64::cos(angle),
+                y: radius * f64::sin(angle),
+            };
+        })
+        .collect();
+}
+
+// svg's y axis points down, so this angle points a corner straight up
+const UP: f64 = -std::f64::consts::FRAC_PI_2;
+
+impl DotShape {
+    /// Looks up one of the built in shapes by name
+    pub fn from_name(name: &str) -> Option<DotShape> {
+        return match name {
+            "circle" => Some(DotShape::Circle),
+            "square" => Some(DotShape::Square),
+            "diamond" => Some(DotShape::Diamond),
+            "triangle" => Some(DotShape::Triangle),
+            "star" => Some(DotShape::Star {
+                points: 5,
+                inner_radius_ratio: 0.5,
+            }),
+            "cross" => Some(DotShape::Cross {
+                arm_width_ratio: 0.5,
+            }),
+            "hollow" => Some(DotShape::Hollow),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            DotShape::Circle => "circle",
+            DotShape::Square => "square",
+            DotShape::Diamond => "diamond",
+            DotShape::Triangle => "triangle",
+            DotShape::Star { .. } => "star",
+            DotShape::Cross { .. } => "cross",
+            DotShape::Hollow => "hollow",
+            DotShape::Glyph(_) => "glyph",
+        };
+    }
+
+    pub fn get_geometry(&self, size: f64) -> ShapeGeometry {
+        if size <= 0.0 {
+            return ShapeGeometry::Empty;
+        }
+
+        return match self {
+            DotShape::Circle => ShapeGeometry::Circle(size),
+            DotShape::Square => ShapeGeometry::Polygons(vec![regular_polygon(
+                4,
+                size * std::f64::consts::SQRT_2,
+                std::f64::consts::FRAC_PI_4,
+            )]),
+            DotShape::Diamond => ShapeGeometry::Polygons(vec![regular_polygon(4, size, UP)]),
+            DotShape::Triangle => ShapeGeometry::Polygons(vec![regular_polygon(3, size, UP)]),
+            DotShape::Star {
+                points,
+                inner_radius_ratio,
+            } => {
+                let outer = regular_polygon(*points, size, UP);
+                let inner = regular_polygon(
+                    *points,
+                    size * inner_radius_ratio,
+                    UP + std::f64::consts::PI / *points as f64,
+                );
+                let mut star: Vec<Vector2> = Vec::with_capacity(outer.len() * 2);
+                for (outer_corner, inner_corner) in outer.into_iter().zip(inner.into_iter()) {
+                    star.push(outer_corner);
+                    star.push(inner_corner);
+                }
+                ShapeGeometry::Polygons(vec![star])
+            }
+            DotShape::Cross { arm_width_ratio } => {
+                let arm = size * arm_width_ratio / 2.0;
+                let corners: [(f64, f64); 12] = [
+                    (-arm, -size),
+                    (arm, -size),
+                    (arm, -arm),
+                    (size, -arm),
+                    (size, arm),
+                    (arm, arm),
+                    (arm, size),
+                    (-arm, size),
+                    (-arm, arm),
+                    (-size, arm),
+                    (-size, -arm),
+                    (-arm, -arm),
+                ];
+                ShapeGeometry::Polygons(vec![corners
+                    .iter()
+                    .map(|(x, y)| Vector2 { x: *x, y: *y })
+                    .collect()])
+            }
+            DotShape::Hollow => ShapeGeometry::Empty,
+            DotShape::Glyph(glyph) => {
+                let polylines = match flatten_path_data(&glyph.path_data) {
+                    Some(polylines) => polylines,
+                    None => return ShapeGeometry::Empty,
+                };
+                // glyphs from loaded documents haven't been through
+                // from_source's check
+                if !has_area(&glyph.view_box) {
+                    return ShapeGeometry::Empty;
+                }
+                let center: Vector2 = glyph.view_box.center();
+                let scale: f64 = 2.0 * size / f64::max(glyph.view_box.width, glyph.view_box.height);
+                ShapeGeometry::Polygons(
+                    polylines
+                        .iter()
+                        .map(|polyline| {
+                            polyline
+                                .iter()
+                                .map(|point| Vector2 {
+                                    x: (point.x - center.x) * scale,
+                                    y: (point.y - center.y) * scale,
+                                })
+                                .collect()
+                        })
+                        .collect(),
+                )
+            }
+        };
+    }
+
+    pub fn get_bounding_radius(&self, size: f64) -> f64 {
+        return self.get_geometry(size).get_bounding_radius();
+    }
+}
+
+impl ShapeGeometry {
+    pub fn get_bounding_radius(&self) -> f64 {
+        return match self {
+            ShapeGeometry::Circle(radius) => *radius,
+            ShapeGeometry::Polygons(polygons) => polygons
+                .iter()
+                .flat_map(|polygon| polygon.iter())
+                .map(|point| f64::sqrt(point.x * point.x + point.y * point.y))
+                .fold(0.0, f64::max),
+            ShapeGeometry::Empty => 0.0,
+        };
+    }
+
+    /// Whether the point is inside the filled part of the shape
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        return match self {
+            ShapeGeometry::Circle(radius) => x * x + y * y <= radius * radius,
+            ShapeGeometry::Polygons(polygons) => {
+                let mut winding: i32 = 0;
+                for polygon in polygons.iter() {
+                    for (index, start) in polygon.iter().enumerate() {
+                        let end = &polygon[(index + 1) % polygon.len()];
+                        let is_left =
+                            (end.x - start.x) * (y - start.y) - (x - start.x) * (end.y - start.y);
+                        if start.y <= y && end.y > y && is_left > 0.0 {
+                            winding += 1;
+                        } else if start.y > y && end.y <= y && is_left < 0.0 {
+                            winding -= 1;
+                        }
+                    }
+                }
+                winding != 0
+            }
+            ShapeGeometry::Empty => false,
+        };
+    }
+}
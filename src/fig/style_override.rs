@@ -0,0 +1,223 @@
+use crate::color::{Color, ColorVision};
+use crate::fig::dot::Dot;
+use crate::fig::shape::DotShape;
+use crate::fig::text_path::ArcStyle;
+use serde::{Deserialize, Serialize};
+
+/// The layer of the style cascade an effective value came from. Later
+/// layers win: default, then preset, then ring, then character, then
+/// bit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleSource {
+    /// The diagram's default dot and arc styles
+    Default,
+    /// The named preset the ring refers to
+    Preset(String),
+    /// The ring's own overrides
+    Ring,
+    /// An override for one character of the ring's text
+    Character(usize),
+    /// An override for a single dot of the ring
+    Bit(usize),
+}
+
+impl StyleSource {
+    pub fn get_description(&self) -> String {
+        return match self {
+            StyleSource::Default => "default".to_string(),
+            StyleSource::Preset(name) => format!("preset {}", name),
+            StyleSource::Ring => "ring".to_string(),
+            StyleSource::Character(char_index) => format!("character {}", char_index + 1),
+            StyleSource::Bit(bit_index) => format!("bit {}", bit_index + 1),
+        };
+    }
+
+    /// Whether the value was set anywhere more specific than the
+    /// defaults
+    pub fn is_overridden(&self) -> bool {
+        return *self != StyleSource::Default;
+    }
+}
+
+/// An effective style value and where it came from
+#[derive(Debug, Clone)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: StyleSource,
+}
+
+impl<T: Clone> Resolved<T> {
+    fn from_default(value: &T) -> Resolved<T> {
+        return Resolved {
+            value: value.clone(),
+            source: StyleSource::Default,
+        };
+    }
+
+    /// Takes `value` from the layer `source`, if the layer sets it
+    fn cascade(&mut self, value: &Option<T>, source: &StyleSource) {
+        if let Some(value) = value {
+            self.value = value.clone();
+            self.source = source.clone();
+        }
+    }
+}
+
+/// Dot fields set by one layer of the cascade. Unset fields are
+/// inherited from the layer below.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DotOverride {
+    pub shape: Option<DotShape>,
+    pub circle_radius: Option<f64>,
+    pub ring_radius: Option<f64>,
+    pub ring_stroke_width: Option<f64>,
+    pub fill_color: Option<Color>,
+    pub ring_color: Option<Color>,
+    pub opacity: Option<f64>,
+}
+
+impl DotOverride {
+    /// An override setting every field to the dot's
+    pub fn from_dot(dot: &Dot) -> DotOverride {
+        return DotOverride {
+            shape: Some(dot.shape.clone()),
+            circle_radius: Some(dot.circle_radius),
+            ring_radius: Some(dot.ring_radius),
+            ring_stroke_width: Some(dot.ring_stroke_width),
+            fill_color: dot.fill_color,
+            ring_color: dot.ring_color,
+            opacity: dot.opacity,
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.shape.is_none()
+            && self.circle_radius.is_none()
+            && self.ring_radius.is_none()
+            && self.ring_stroke_width.is_none()
+            && self.fill_color.is_none()
+            && self.ring_color.is_none()
+            && self.opacity.is_none();
+    }
+
+    pub fn simulate_vision(&self, vision: &ColorVision) -> DotOverride {
+        return DotOverride {
+            fill_color: self.fill_color.map(|color| color.simulate_vision(vision)),
+            ring_color: self.ring_color.map(|color| color.simulate_vision(vision)),
+            ..self.clone()
+        };
+    }
+}
+
+/// Arc fields set by one layer of the cascade
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArcOverride {
+    pub radius: Option<f64>,
+    pub arc_percentage: Option<f64>,
+    pub arc_offset_percentage: Option<f64>,
+}
+
+impl ArcOverride {
+    pub fn from_arc_style(arc_style: &ArcStyle) -> ArcOverride {
+        return ArcOverride {
+            radius: Some(arc_style.radius),
+            arc_percentage: Some(arc_style.arc_percentage),
+            arc_offset_percentage: Some(arc_style.arc_offset_percentage),
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.radius.is_none()
+            && self.arc_percentage.is_none()
+            && self.arc_offset_percentage.is_none();
+    }
+}
+
+/// A dot's effective style, field by field
+#[derive(Debug, Clone)]
+pub struct ResolvedDot {
+    pub shape: Resolved<DotShape>,
+    pub circle_radius: Resolved<f64>,
+    pub ring_radius: Resolved<f64>,
+    pub ring_stroke_width: Resolved<f64>,
+    /// None where the dot takes its color from the color mode
+    pub fill_color: Resolved<Option<Color>>,
+    pub ring_color: Resolved<Option<Color>>,
+    pub opacity: Resolved<Option<f64>>,
+}
+
+impl ResolvedDot {
+    pub fn from_default(dot: &Dot) -> ResolvedDot {
+        return ResolvedDot {
+            shape: Resolved::from_default(&dot.shape),
+            circle_radius: Resolved::from_default(&dot.circle_radius),
+            ring_radius: Resolved::from_default(&dot.ring_radius),
+            ring_stroke_width: Resolved::from_default(&dot.ring_stroke_width),
+            fill_color: Resolved::from_default(&dot.fill_color),
+            ring_color: Resolved::from_default(&dot.ring_color),
+            opacity: Resolved::from_default(&dot.opacity),
+        };
+    }
+
+    /// Applies the fields `dot_override` sets on top of the current ones
+    pub fn cascade(&mut self, dot_override: &DotOverride, source: &StyleSource) {
+        self.shape.cascade(&dot_override.shape, source);
+        self.circle_radius
+            .cascade(&dot_override.circle_radius, source);
+        self.ring_radius.cascade(&dot_override.ring_radius, source);
+        self.ring_stroke_width
+            .cascade(&dot_override.ring_stroke_width, source);
+        self.fill_color
+            .cascade(&dot_override.fill_color.map(Some), source);
+        self.ring_color
+            .cascade(&dot_override.ring_color.map(Some), source);
+        self.opacity
+            .cascade(&dot_override.opacity.map(Some), source);
+    }
+
+    pub fn to_dot(&self) -> Dot {
+        return Dot {
+            shape: self.shape.value.clone(),
+            circle_radius: self.circle_radius.value,
+            ring_radius: self.ring_radius.value,
+            ring_stroke_width: self.ring_stroke_width.value,
+            fill_color: self.fill_color.value,
+            ring_color: self.ring_color.value,
+            opacity: self.opacity.value,
+        };
+    }
+}
+
+/// An arc's effective style, field by field
+#[derive(Debug, Clone)]
+pub struct ResolvedArc {
+    pub radius: Resolved<f64>,
+    pub arc_percentage: Resolved<f64>,
+    pub arc_offset_percentage: Resolved<f64>,
+}
+
+impl ResolvedArc {
+    pub fn from_default(arc_style: &ArcStyle) -> ResolvedArc {
+        return ResolvedArc {
+            radius: Resolved::from_default(&arc_style.radius),
+            arc_percentage: Resolved::from_default(&arc_style.arc_percentage),
+            arc_offset_percentage: Resolved::from_default(&arc_style.arc_offset_percentage),
+        };
+    }
+
+    pub fn cascade(&mut self, arc_override: &ArcOverride, source: &StyleSource) {
+        self.radius.cascade(&arc_override.radius, source);
+        self.arc_percentage
+            .cascade(&arc_override.arc_percentage, source);
+        self.arc_offset_percentage
+            .cascade(&arc_override.arc_offset_percentage, source);
+    }
+
+    pub fn to_arc_style(&self) -> ArcStyle {
+        return ArcStyle {
+            radius: self.radius.value,
+            arc_percentage: self.arc_percentage.value,
+            arc_offset_percentage: self.arc_offset_percentage.value,
+        };
+    }
+}
@@ -1,9 +1,16 @@
-use crate::drawing_style::DrawingStyle;
+use crate::canvas::canvas_context::CanvasContext;
+use crate::canvas::canvas_drawable::CanvasDrawable;
+use crate::color::Color;
+use crate::drawing_style::{DotPosition, DrawingStyle, StylePreset};
 use crate::fig::dot::Dot;
+use crate::fig::style_override::{ArcOverride, DotOverride, ResolvedArc, ResolvedDot, StyleSource};
 use crate::float_utils::fmax;
 use crate::svg::svg_drawable::SvgDrawable;
-use crate::svg::util::translate_svg;
-use crate::utf_to_binary::text_to_binary;
+use crate::svg::util::{translate_svg, translate_transform};
+use crate::svg::virtual_svg_drawable::VirtualSvgDrawable;
+use crate::utf_to_binary::{text_to_binary, BITS_PER_CHAR};
+use serde::{Deserialize, Serialize};
+use virtual_dom_rs::*;
 // use wasm_bindgen::prelude::*;
 
 // extern "C" {
@@ -11,102 +18,331 @@ use crate::utf_to_binary::text_to_binary;
 //     fn log(s: &str);
 // }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArcStyle {
     pub radius: f64,
     pub arc_percentage: f64,
     pub arc_offset_percentage: f64,
 }
 
-#[derive(Debug)]
+/// A single dot laid out on a text path
+///
+/// Positions are relative to the center of the path.
+pub struct DotPlacement {
+    pub x: f64,
+    pub y: f64,
+    pub angle: f64,
+    pub bit_index: usize,
+    /// Index of the character in the path's text that this bit encodes
+    pub char_index: usize,
+    pub value: bool,
+    /// The dot's effective style, after every override
+    pub dot: Dot,
+    /// Color picked for the dot by the style's color mode
+    pub color: Color,
+}
+
+/// The dots of a ring that a targeted override applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "index", rename_all = "snake_case")]
+pub enum DotTarget {
+    /// Every dot encoding the character at this index of the text
+    Character(usize),
+    /// The dot at this index along the ring
+    Bit(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetedDotOverride {
+    pub target: DotTarget,
+    pub style: DotOverride,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextPath {
-    pub zero_dot_style: Option<Dot>,
-    pub one_dot_style: Option<Dot>,
-    pub arc_style: Option<ArcStyle>,
+    /// Name of a preset in the style that sits between the defaults and
+    /// the path's own overrides
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub zero_dot_style: DotOverride,
+    #[serde(default)]
+    pub one_dot_style: DotOverride,
+    #[serde(default)]
+    pub arc_style: ArcOverride,
+    /// Overrides for single characters or dots. Character overrides
+    /// apply before bit overrides, whatever their order here.
+    #[serde(default)]
+    pub dot_overrides: Vec<TargetedDotOverride>,
     pub text: String,
 }
 
-impl<'style_and_self_lifetime> TextPath {
-    fn get_arc_style(
-        &'style_and_self_lifetime self,
-        style: &'style_and_self_lifetime DrawingStyle,
-    ) -> &'style_and_self_lifetime ArcStyle {
-        match &self.arc_style {
-            Some(style) => &style,
-            None => &style.default_arc_style,
-        }
+impl TextPath {
+    /// A path styled entirely by the defaults
+    pub fn new(text: String) -> TextPath {
+        return TextPath {
+            preset: None,
+            zero_dot_style: DotOverride::default(),
+            one_dot_style: DotOverride::default(),
+            arc_style: ArcOverride::default(),
+            dot_overrides: vec![],
+            text: text,
+        };
     }
 
-    fn get_zero_dot_style(
-        &'style_and_self_lifetime self,
-        style: &'style_and_self_lifetime DrawingStyle,
-    ) -> &'style_and_self_lifetime Dot {
-        match &self.zero_dot_style {
-            Some(style) => &style,
-            None => &style.default_zero_dot_style,
+    /// Replaces the path's text. Character and bit overrides were placed
+    /// for the old text, so they're dropped if the text changes.
+    pub fn set_text(&mut self, text: String) {
+        if self.text != text {
+            self.dot_overrides = vec![];
+            self.text = text;
         }
     }
 
-    fn get_one_dot_style(
-        &'style_and_self_lifetime self,
-        style: &'style_and_self_lifetime DrawingStyle,
-    ) -> &'style_and_self_lifetime Dot {
-        match &self.one_dot_style {
-            Some(style) => &style,
-            None => &style.default_one_dot_style,
+    fn get_preset<'a>(&self, style: &'a DrawingStyle) -> Option<&'a StylePreset> {
+        return match &self.preset {
+            Some(preset_name) => style.get_preset(preset_name),
+            None => None,
+        };
+    }
+
+    /// The path's effective arc style, with where each field came from
+    pub fn resolve_arc_style(&self, style: &DrawingStyle) -> ResolvedArc {
+        let mut resolved = ResolvedArc::from_default(&style.default_arc_style);
+        if let Some(preset) = self.get_preset(style) {
+            resolved.cascade(&preset.arc_style, &StyleSource::Preset(preset.name.clone()));
         }
+        resolved.cascade(&self.arc_style, &StyleSource::Ring);
+        return resolved;
     }
 
-    pub fn get_bounding_radius(&self, style: &DrawingStyle) -> f64 {
-        let arc_style: &ArcStyle = self.get_arc_style(style);
-        let zero_dot_style: &Dot = self.get_zero_dot_style(style);
-        let one_dot_style: &Dot = self.get_one_dot_style(style);
-
-        return arc_style.radius
-            + fmax(
-                &one_dot_style.get_bounding_radius(),
-                &zero_dot_style.get_bounding_radius(),
-            ) / 2.0;
+    /// The effective style of the path's one or zero dots, with where
+    /// each field came from. Without a `bit_index`, only the defaults,
+    /// preset and ring overrides are applied.
+    pub fn resolve_dot_style(
+        &self,
+        style: &DrawingStyle,
+        value: bool,
+        bit_index: Option<usize>,
+    ) -> ResolvedDot {
+        let (default_dot, preset_dot, ring_dot) = if value {
+            (
+                &style.default_one_dot_style,
+                self.get_preset(style)
+                    .map(|preset| (preset, &preset.one_dot_style)),
+                &self.one_dot_style,
+            )
+        } else {
+            (
+                &style.default_zero_dot_style,
+                self.get_preset(style)
+                    .map(|preset| (preset, &preset.zero_dot_style)),
+                &self.zero_dot_style,
+            )
+        };
+
+        let mut resolved = ResolvedDot::from_default(default_dot);
+        if let Some((preset, preset_dot)) = preset_dot {
+            resolved.cascade(preset_dot, &StyleSource::Preset(preset.name.clone()));
+        }
+        resolved.cascade(ring_dot, &StyleSource::Ring);
+
+        if let Some(bit_index) = bit_index {
+            let char_index: usize = bit_index / BITS_PER_CHAR;
+            for dot_override in self.dot_overrides.iter() {
+                if dot_override.target == DotTarget::Character(char_index) {
+                    resolved.cascade(&dot_override.style, &StyleSource::Character(char_index));
+                }
+            }
+            for dot_override in self.dot_overrides.iter() {
+                if dot_override.target == DotTarget::Bit(bit_index) {
+                    resolved.cascade(&dot_override.style, &StyleSource::Bit(bit_index));
+                }
+            }
+        }
+        return resolved;
     }
-}
 
-impl SvgDrawable for TextPath {
-    /// Builds an svg for the text path
-    ///
-    /// The text path is radial and centered on the point (0,0)
-    fn as_svg(&self, style: &DrawingStyle) -> String {
-        let mut text_binary = match text_to_binary(&self.text) {
+    /// Lays out the dots of the path around the point (0,0), colored as
+    /// ring `ring_index` of the `ring_count` rings in the diagram
+    pub fn get_dot_placements(
+        &self,
+        style: &DrawingStyle,
+        ring_index: usize,
+        ring_count: usize,
+    ) -> Vec<DotPlacement> {
+        let text_binary = match text_to_binary(&self.text) {
             Some(text_binary) => text_binary,
             None => vec![],
         };
 
-        let zero_dot_string: String = self.get_zero_dot_style(style).as_svg(style);
-        let one_dot_string: String = self.get_one_dot_style(style).as_svg(style);
-
-        let mut dots: Vec<String> = Vec::with_capacity(text_binary.len());
-        let arc_style = self.get_arc_style(style);
+        let zero_dot_style: Dot = self.resolve_dot_style(style, false, None).to_dot();
+        let one_dot_style: Dot = self.resolve_dot_style(style, true, None).to_dot();
+        let arc_style: ArcStyle = self.resolve_arc_style(style).to_arc_style();
 
         let initial_angle = arc_style.arc_offset_percentage * std::f64::consts::PI * 2.0;
         let arc_range_angle = arc_style.arc_percentage * std::f64::consts::PI * 2.0;
         let num_dots = text_binary.len();
 
-        for (index, current) in text_binary.iter_mut().enumerate() {
-            let dot: &str = if *current {
-                &one_dot_string
+        let mut placements: Vec<DotPlacement> = Vec::with_capacity(num_dots);
+        for (index, current) in text_binary.iter().enumerate() {
+            let arc_percent = (index + 1) as f64 / (num_dots) as f64;
+            let angle = initial_angle + arc_percent * arc_range_angle;
+
+            // only dots with targeted overrides need resolving one by one
+            let dot: Dot = if !self.dot_overrides.is_empty() {
+                self.resolve_dot_style(style, *current, Some(index))
+                    .to_dot()
+            } else if *current {
+                one_dot_style.clone()
             } else {
-                &zero_dot_string
+                zero_dot_style.clone()
             };
 
-            let arc_percent = (index + 1) as f64 / (num_dots) as f64;
-            let angle = initial_angle + arc_percent * arc_range_angle;
+            placements.push(DotPlacement {
+                x: arc_style.radius * f64::cos(angle),
+                y: arc_style.radius * f64::sin(angle),
+                angle: angle,
+                bit_index: index,
+                char_index: index / BITS_PER_CHAR,
+                value: *current,
+                dot: dot,
+                color: style.get_dot_color(&DotPosition {
+                    ring_index: ring_index,
+                    ring_count: ring_count,
+                    dot_index: index,
+                    dot_count: num_dots,
+                }),
+            });
+        }
+
+        return placements;
+    }
+
+    /// The largest bounding radius of the path's dots
+    fn get_max_dot_radius(&self, style: &DrawingStyle) -> f64 {
+        let mut max_dot_radius: f64 = fmax(
+            &self
+                .resolve_dot_style(style, false, None)
+                .to_dot()
+                .get_bounding_radius(),
+            &self
+                .resolve_dot_style(style, true, None)
+                .to_dot()
+                .get_bounding_radius(),
+        );
+        if !self.dot_overrides.is_empty() {
+            for placement in self.get_dot_placements(style, 0, 1).iter() {
+                max_dot_radius = fmax(&max_dot_radius, &placement.dot.get_bounding_radius());
+            }
+        }
+        return max_dot_radius;
+    }
 
-            let x = arc_style.radius * f64::cos(angle);
-            let y = arc_style.radius * f64::sin(angle);
+    pub fn get_bounding_radius(&self, style: &DrawingStyle) -> f64 {
+        return self.resolve_arc_style(style).radius.value + self.get_max_dot_radius(style);
+    }
+
+    /// Distance from the center to the inner edge of the path's dots
+    pub fn get_inner_radius(&self, style: &DrawingStyle) -> f64 {
+        return self.resolve_arc_style(style).radius.value - self.get_max_dot_radius(style);
+    }
 
-            let moved_dot = translate_svg(&dot, x, y);
+    /// Builds an svg for the text path as ring `ring_index` of
+    /// `ring_count`
+    ///
+    /// The text path is radial and centered on the point (0,0)
+    pub fn as_ring_svg(
+        &self,
+        style: &DrawingStyle,
+        ring_index: usize,
+        ring_count: usize,
+    ) -> String {
+        let placements: Vec<DotPlacement> = self.get_dot_placements(style, ring_index, ring_count);
+        let mut dots: Vec<String> = Vec::with_capacity(placements.len());
+
+        for placement in placements.iter() {
+            let dot: String = placement.dot.as_colored_svg(&placement.color);
+            let moved_dot = translate_svg(&dot, placement.x, placement.y);
             dots.push(moved_dot);
         }
 
         return dots.join("");
     }
+
+    /// Builds a virtual svg group for the text path as ring
+    /// `ring_index` of `ring_count`
+    ///
+    /// Each dot gets its own group tagged with its bit index and value,
+    /// so diffs only touch the dots that changed.
+    pub fn as_ring_virtual_svg(
+        &self,
+        style: &DrawingStyle,
+        ring_index: usize,
+        ring_count: usize,
+    ) -> VirtualNode {
+        let dots: Vec<VirtualNode> = self
+            .get_dot_placements(style, ring_index, ring_count)
+            .iter()
+            .map(|placement| {
+                let transform: String = translate_transform(placement.x, placement.y);
+                let dot_class: &str = if placement.value {
+                    "dot dot-one"
+                } else {
+                    "dot dot-zero"
+                };
+                return html! {
+                    <g
+                        class=dot_class
+                        transform=transform
+                        data_bit_index={placement.bit_index}
+                        data_bit_value={placement.value}>
+                        {placement.dot.as_colored_virtual_svg(&placement.color)}
+                    </g>
+                };
+            })
+            .collect();
+
+        return html! {
+            <g class="text-path">{dots}</g>
+        };
+    }
+
+    /// Draws the text path as ring `ring_index` of `ring_count`,
+    /// centered on the context's current origin
+    pub fn draw_ring(
+        &self,
+        context: &mut dyn CanvasContext,
+        style: &DrawingStyle,
+        ring_index: usize,
+        ring_count: usize,
+    ) {
+        for placement in self
+            .get_dot_placements(style, ring_index, ring_count)
+            .iter()
+        {
+            context.save();
+            context.translate(placement.x, placement.y);
+            placement.dot.draw_colored(context, &placement.color);
+            context.restore();
+        }
+    }
+}
+
+// Drawn on its own, a text path is colored as the only ring
+impl SvgDrawable for TextPath {
+    fn as_svg(&self, style: &DrawingStyle) -> String {
+        return self.as_ring_svg(style, 0, 1);
+    }
+}
+
+impl VirtualSvgDrawable for TextPath {
+    fn as_virtual_svg(&self, style: &DrawingStyle) -> VirtualNode {
+        return self.as_ring_virtual_svg(style, 0, 1);
+    }
+}
+
+impl CanvasDrawable for TextPath {
+    fn draw(&self, context: &mut dyn CanvasContext, style: &DrawingStyle) {
+        self.draw_ring(context, style, 0, 1);
+    }
 }
@@ -1,3 +1,7 @@
 pub fn fmax(a: &f64, b: &f64) -> f64 {
     return if a > b { *a } else { *b };
 }
+
+pub fn fmin(a: &f64, b: &f64) -> f64 {
+    return if a < b { *a } else { *b };
+}
@@ -1,3 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub x: f64,
     pub y: f64,
@@ -5,6 +8,7 @@ pub struct Rect {
     pub height: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vector2 {
     pub x: f64,
     pub y: f64,
@@ -18,3 +22,90 @@ impl Rect {
         };
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    pub fn sub(&self, other: &Vector3) -> Vector3 {
+        return Vector3 {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        };
+    }
+
+    pub fn cross(&self, other: &Vector3) -> Vector3 {
+        return Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        };
+    }
+
+    pub fn normalized(&self) -> Vector3 {
+        let length = f64::sqrt(self.x * self.x + self.y * self.y + self.z * self.z);
+        if length == 0.0 {
+            return *self;
+        }
+        return Vector3 {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+        };
+    }
+}
+
+const MILLIMETERS_PER_INCH: f64 = 25.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    /// The diagram's own coordinate units
+    #[serde(rename = "units")]
+    Units,
+    #[serde(rename = "mm")]
+    Millimeters,
+    #[serde(rename = "in")]
+    Inches,
+}
+
+impl LengthUnit {
+    pub fn from_name(name: &str) -> Option<LengthUnit> {
+        return match name {
+            "units" => Some(LengthUnit::Units),
+            "mm" => Some(LengthUnit::Millimeters),
+            "in" => Some(LengthUnit::Inches),
+            _ => None,
+        };
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            LengthUnit::Units => "units",
+            LengthUnit::Millimeters => "mm",
+            LengthUnit::Inches => "in",
+        };
+    }
+}
+
+/// A length in diagram units, or in physical units that are converted
+/// to diagram units by the print scale
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Length {
+    pub value: f64,
+    pub unit: LengthUnit,
+}
+
+impl Length {
+    pub fn to_units(&self, millimeters_per_unit: f64) -> f64 {
+        return match self.unit {
+            LengthUnit::Units => self.value,
+            LengthUnit::Millimeters => self.value / millimeters_per_unit,
+            LengthUnit::Inches => self.value * MILLIMETERS_PER_INCH / millimeters_per_unit,
+        };
+    }
+}
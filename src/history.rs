@@ -0,0 +1,63 @@
+/// How many edits are kept to undo. Older ones are forgotten.
+const MAX_UNDO_ENTRIES: usize = 100;
+
+/// Undo and redo stacks of snapshots, each taken before an edit
+#[derive(Debug)]
+pub struct History<T> {
+    undo_stack: Vec<T>,
+    redo_stack: Vec<T>,
+    /// Key of the edit still in progress, like a slider being dragged.
+    /// Later edits with the same key are part of its entry.
+    open_edit_key: Option<String>,
+}
+
+impl<T> History<T> {
+    pub fn new() -> History<T> {
+        return History {
+            undo_stack: vec![],
+            redo_stack: vec![],
+            open_edit_key: None,
+        };
+    }
+
+    /// Records `before`, the state as it was before an edit.
+    ///
+    /// Edits with the same key as the open edit don't get an entry of
+    /// their own, so every input event of one slider drag undoes at once.
+    /// Edits without a key always get their own entry.
+    pub fn record(&mut self, before: T, edit_key: Option<String>) {
+        self.redo_stack.clear();
+        if edit_key.is_some() && edit_key == self.open_edit_key {
+            return;
+        }
+
+        self.undo_stack.push(before);
+        if self.undo_stack.len() > MAX_UNDO_ENTRIES {
+            self.undo_stack.remove(0);
+        }
+        self.open_edit_key = edit_key;
+    }
+
+    /// Ends the open edit, so the next edit gets its own entry even if
+    /// it has the same key. Called when a slider is let go.
+    pub fn finish_edit(&mut self) {
+        self.open_edit_key = None;
+    }
+
+    /// Steps back one entry. `current` is kept to redo, and the state to
+    /// restore is returned, or None when there is nothing to undo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous: T = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+        self.open_edit_key = None;
+        return Some(previous);
+    }
+
+    /// Steps forward over an undone entry, the reverse of `undo`
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next: T = self.redo_stack.pop()?;
+        self.undo_stack.push(current);
+        self.open_edit_key = None;
+        return Some(next);
+    }
+}
@@ -2,11 +2,23 @@
 #![feature(proc_macro_hygiene)]
 
 
+mod action;
+mod canvas;
+mod color;
+mod color_check;
+mod document;
 mod drawing_style;
+mod error;
 mod fig;
 mod float_utils;
 mod geom;
+mod history;
+mod markup;
+mod raster;
+mod share;
 mod start;
+mod stl;
 mod svg;
+mod themes;
 mod utf_to_binary;
 
@@ -0,0 +1,101 @@
+use crate::svg::util::escape_xml;
+use virtual_dom_rs::prelude::*;
+use virtual_dom_rs::VElement;
+
+/// Html elements written without a closing tag, as virtual-dom-rs
+/// writes them
+const VOID_TAGS: [&str; 13] = [
+    "area", "base", "br", "col", "hr", "img", "input", "link", "meta", "param", "command",
+    "keygen", "source",
+];
+
+/// Comment virtual-dom-rs puts between neighbouring text nodes when it
+/// creates them, so the browser doesn't merge them and patches still
+/// find each one
+const TEXT_SEPARATOR: &str = "<!--ptns-->";
+
+fn write_element(element: &VElement, markup: &mut String) {
+    markup.push('<');
+    markup.push_str(&element.tag);
+    // sorted, so the same tree is always written the same way
+    let mut attrs: Vec<(&String, &String)> = element.attrs.iter().collect();
+    attrs.sort();
+    for (name, value) in attrs.into_iter() {
+        markup.push_str(&format!(" {}=\"{}\"", name, escape_xml(value)));
+    }
+    markup.push('>');
+
+    let mut previous_was_text: bool = false;
+    for child in element.children.iter() {
+        let is_text: bool = match child {
+            VirtualNode::Text(_) => true,
+            VirtualNode::Element(_) => false,
+        };
+        if is_text && previous_was_text {
+            markup.push_str(TEXT_SEPARATOR);
+        }
+        write_node(child, markup);
+        previous_was_text = is_text;
+    }
+
+    if !VOID_TAGS.contains(&element.tag.as_str()) {
+        markup.push_str(&format!("</{}>", element.tag));
+    }
+}
+
+fn write_node(node: &VirtualNode, markup: &mut String) {
+    match node {
+        VirtualNode::Element(element) => write_element(element, markup),
+        VirtualNode::Text(text) => markup.push_str(&escape_xml(&text.text)),
+    };
+}
+
+/// Writes a virtual tree as markup to set as an element's inner html.
+///
+/// Unlike `to_string`, text and attribute values are escaped, so text
+/// from a design can't add elements or scripts to the page.
+pub fn to_markup(node: &VirtualNode) -> String {
+    let mut markup: String = String::new();
+    write_node(node, &mut markup);
+    return markup;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_attribute_values_are_escaped() {
+        let mut title = VElement::new("title");
+        title
+            .children
+            .push(VirtualNode::text("</title><script>alert(1)</script>"));
+        let mut input = VElement::new("input");
+        input
+            .attrs
+            .insert("value".to_string(), "\"><img src=x>".to_string());
+        let mut group = VElement::new("g");
+        group.children.push(VirtualNode::Element(title));
+        group.children.push(VirtualNode::Element(input));
+
+        assert_eq!(
+            to_markup(&VirtualNode::Element(group)),
+            concat!(
+                "<g><title>&lt;/title&gt;&lt;script&gt;alert(1)&lt;/script&gt;</title>",
+                "<input value=\"&quot;&gt;&lt;img src=x&gt;\"></g>"
+            )
+        );
+    }
+
+    #[test]
+    fn neighbouring_text_nodes_are_kept_apart() {
+        let mut label = VElement::new("label");
+        label.children.push(VirtualNode::text("a"));
+        label.children.push(VirtualNode::text("b"));
+
+        assert_eq!(
+            to_markup(&VirtualNode::Element(label)),
+            "<label>a<!--ptns-->b</label>"
+        );
+    }
+}
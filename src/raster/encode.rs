@@ -0,0 +1,56 @@
+use crate::raster::rasterizer::RasterImage;
+use png::HasParameters;
+
+/// Encodes frames as a looping animated gif.
+///
+/// Gif frame delays are in hundredths of a second, so the frame rate is
+/// rounded to the nearest representable delay. None if there are no
+/// frames, or they are larger than a gif can hold.
+pub fn encode_gif(frames: &mut Vec<RasterImage>, frames_per_second: f64) -> Option<Vec<u8>> {
+    // scoped here since its blanket impl clashes with png's HasParameters
+    use gif::SetParameter;
+    use std::convert::TryFrom;
+
+    let (width, height) = match frames.first() {
+        Some(frame) => (
+            u16::try_from(frame.width).ok()?,
+            u16::try_from(frame.height).ok()?,
+        ),
+        None => return None,
+    };
+    let delay: f64 = f64::round(100.0 / frames_per_second);
+    if !(delay >= 1.0 && delay <= u16::max_value() as f64) {
+        return None;
+    }
+    let delay: u16 = delay as u16;
+
+    let mut bytes: Vec<u8> = vec![];
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, width, height, &[]).ok()?;
+        encoder.set(gif::Repeat::Infinite).ok()?;
+        for frame in frames.iter_mut() {
+            let mut gif_frame = gif::Frame::from_rgba(width, height, &mut frame.pixels);
+            gif_frame.delay = delay;
+            encoder.write_frame(&gif_frame).ok()?;
+        }
+    }
+    return Some(bytes);
+}
+
+pub fn encode_png(image: &RasterImage) -> Option<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![];
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, image.width, image.height);
+        encoder.set(png::ColorType::RGBA).set(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().ok()?;
+        writer.write_image_data(&image.pixels).ok()?;
+    }
+    return Some(bytes);
+}
+
+/// File name for one frame of a numbered png sequence, zero padded so
+/// the frames sort in order
+pub fn png_frame_file_name(frame_index: u32, frame_count: u32) -> String {
+    let digits: usize = frame_count.to_string().len();
+    return format!("frame-{:0width$}.png", frame_index, width = digits);
+}
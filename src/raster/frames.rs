@@ -0,0 +1,127 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::drawing_style::DrawingStyle;
+use crate::fig::diagram::Diagram;
+use crate::fig::text_path::DotPlacement;
+use crate::geom::{Rect, Vector2};
+use crate::raster::rasterizer::{RasterContext, RasterImage};
+
+pub enum FrameAnimation {
+    /// Dots appear one at a time, ring by ring, as if being typed out
+    TypeOut,
+    /// The whole diagram makes one full turn about its center
+    Rotate,
+}
+
+impl FrameAnimation {
+    pub fn from_name(name: &str) -> Option<FrameAnimation> {
+        return match name {
+            "type-out" => Some(FrameAnimation::TypeOut),
+            "rotate" => Some(FrameAnimation::Rotate),
+            _ => None,
+        };
+    }
+}
+
+/// Lays out the diagram partway through an animation.
+///
+/// `time` runs from 0 at the start of the animation to 1 at the end.
+pub fn get_dot_placements_at_time(
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    animation: &FrameAnimation,
+    time: f64,
+) -> Vec<DotPlacement> {
+    let mut placements: Vec<DotPlacement> = diagram.get_dot_placements(style);
+
+    match animation {
+        FrameAnimation::TypeOut => {
+            let visible_dots = f64::ceil(placements.len() as f64 * time) as usize;
+            placements.truncate(visible_dots);
+        }
+        FrameAnimation::Rotate => {
+            let center: Vector2 = diagram.get_bounding_rect(style).center();
+            let rotation = time * std::f64::consts::PI * 2.0;
+            for placement in placements.iter_mut() {
+                let dx = placement.x - center.x;
+                let dy = placement.y - center.y;
+                placement.x = center.x + dx * f64::cos(rotation) - dy * f64::sin(rotation);
+                placement.y = center.y + dx * f64::sin(rotation) + dy * f64::cos(rotation);
+                placement.angle += rotation;
+            }
+        }
+    }
+
+    return placements;
+}
+
+/// Height in pixels of the frames of the diagram at `width` pixels wide
+pub fn get_frame_height(diagram: &Diagram, style: &DrawingStyle, width: u32) -> u32 {
+    let diagram_bounds: Rect = diagram.get_bounding_rect(style);
+    let scale: f64 = width as f64 / diagram_bounds.width;
+    return f64::ceil(diagram_bounds.height * scale) as u32;
+}
+
+/// Rasterizes the diagram at `time` through the animation, scaled to
+/// `width` pixels wide. None if the image would be too large to address.
+pub fn render_frame(
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    animation: &FrameAnimation,
+    time: f64,
+    width: u32,
+) -> Option<RasterImage> {
+    let diagram_bounds: Rect = diagram.get_bounding_rect(style);
+    let scale: f64 = width as f64 / diagram_bounds.width;
+    let height: u32 = get_frame_height(diagram, style, width);
+
+    let mut context = RasterContext::new(width, height)?;
+    context.scale(scale, scale);
+    context.translate(-diagram_bounds.x, -diagram_bounds.y);
+    diagram
+        .background
+        .draw(&mut context, &diagram_bounds, &style.background_color);
+
+    for placement in get_dot_placements_at_time(diagram, style, animation, time).iter() {
+        context.save();
+        context.translate(placement.x, placement.y);
+        placement.dot.draw_colored(&mut context, &placement.color);
+        context.restore();
+    }
+
+    return Some(context.into_image());
+}
+
+/// The animation time of frame `frame_index` out of `frame_count`
+/// evenly spaced frames of one loop.
+///
+/// Rotations stop one frame short of a full turn so that looping
+/// playback doesn't repeat a frame. Typing ends on the full diagram.
+pub fn frame_time(animation: &FrameAnimation, frame_index: u32, frame_count: u32) -> f64 {
+    let time_divisor: f64 = match animation {
+        FrameAnimation::TypeOut => f64::max(frame_count as f64 - 1.0, 1.0),
+        FrameAnimation::Rotate => frame_count as f64,
+    };
+    return frame_index as f64 / time_divisor;
+}
+
+/// Rasterizes every frame of one loop of the animation, or None if the
+/// frames would be too large to address
+pub fn render_frames(
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    animation: &FrameAnimation,
+    frame_count: u32,
+    width: u32,
+) -> Option<Vec<RasterImage>> {
+    return (0..frame_count)
+        .map(|frame_index| {
+            render_frame(
+                diagram,
+                style,
+                animation,
+                frame_time(animation, frame_index, frame_count),
+                width,
+            )
+        })
+        .collect();
+}
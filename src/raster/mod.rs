@@ -0,0 +1,3 @@
+pub mod encode;
+pub mod frames;
+pub mod rasterizer;
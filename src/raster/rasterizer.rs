@@ -0,0 +1,236 @@
+use crate::canvas::canvas_context::CanvasContext;
+use crate::color::Color;
+use crate::fig::shape::ShapeGeometry;
+use crate::geom::{Rect, Vector2};
+
+/// Samples per pixel along each axis when filling polygons
+const POLYGON_SUPERSAMPLING: u32 = 4;
+
+/// An RGBA image with 8 bits per channel, stored row by row
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    return if value < 0.0 {
+        0.0
+    } else if value > 1.0 {
+        1.0
+    } else {
+        value
+    };
+}
+
+#[derive(Clone, Copy)]
+struct DrawState {
+    scale_x: f64,
+    scale_y: f64,
+    translate_x: f64,
+    translate_y: f64,
+    opacity: f64,
+}
+
+/// A canvas context that draws into an in-memory image, so diagrams can
+/// be rendered without a browser.
+///
+/// Only translation and scaling are supported, which is all the
+/// drawables use. Circles are anti-aliased by pixel coverage.
+pub struct RasterContext {
+    image: RasterImage,
+    state: DrawState,
+    saved_states: Vec<DrawState>,
+}
+
+impl RasterContext {
+    /// A transparent image of the given size, or None if its pixels
+    /// can't be addressed
+    pub fn new(width: u32, height: u32) -> Option<RasterContext> {
+        let pixel_bytes: usize = (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(4)?;
+        return Some(RasterContext {
+            image: RasterImage {
+                width: width,
+                height: height,
+                pixels: vec![0; pixel_bytes],
+            },
+            state: DrawState {
+                scale_x: 1.0,
+                scale_y: 1.0,
+                translate_x: 0.0,
+                translate_y: 0.0,
+                opacity: 1.0,
+            },
+            saved_states: vec![],
+        });
+    }
+
+    pub fn into_image(self) -> RasterImage {
+        return self.image;
+    }
+
+    /// Blends `color` over the pixel at (x, y), weighted by `coverage`
+    fn blend_pixel(&mut self, x: u32, y: u32, color: &[u8; 4], coverage: f64) {
+        let alpha = color[3] as f64 / 255.0 * coverage * self.state.opacity;
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let offset: usize = (y as usize * self.image.width as usize + x as usize) * 4;
+        let pixel = &mut self.image.pixels[offset..offset + 4];
+        for channel in 0..3 {
+            pixel[channel] = (color[channel] as f64 * alpha + pixel[channel] as f64 * (1.0 - alpha))
+                .round() as u8;
+        }
+        pixel[3] = ((alpha + pixel[3] as f64 / 255.0 * (1.0 - alpha)) * 255.0).round() as u8;
+    }
+
+    /// Calls `coverage` with the distance from the current origin to the
+    /// center of every pixel within `radius` of it (in pixels), and
+    /// blends `color` into each pixel by the returned coverage.
+    fn blend_radially<F: Fn(f64) -> f64>(&mut self, radius: f64, color: &Color, coverage: F) {
+        let color = color.to_rgba8();
+        let center_x = self.state.translate_x;
+        let center_y = self.state.translate_y;
+        let reach = radius + 1.0;
+
+        let min_x = f64::max(f64::floor(center_x - reach), 0.0) as u32;
+        let min_y = f64::max(f64::floor(center_y - reach), 0.0) as u32;
+        let max_x = f64::min(f64::ceil(center_x + reach), self.image.width as f64) as u32;
+        let max_y = f64::min(f64::ceil(center_y + reach), self.image.height as f64) as u32;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let dx = x as f64 + 0.5 - center_x;
+                let dy = y as f64 + 0.5 - center_y;
+                let pixel_coverage = coverage(f64::sqrt(dx * dx + dy * dy));
+                self.blend_pixel(x, y, &color, pixel_coverage);
+            }
+        }
+    }
+}
+
+impl CanvasContext for RasterContext {
+    fn save(&mut self) {
+        self.saved_states.push(self.state);
+    }
+
+    fn restore(&mut self) {
+        match self.saved_states.pop() {
+            Some(state) => self.state = state,
+            None => {}
+        }
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        self.state.translate_x += x * self.state.scale_x;
+        self.state.translate_y += y * self.state.scale_y;
+    }
+
+    fn scale(&mut self, x: f64, y: f64) {
+        self.state.scale_x *= x;
+        self.state.scale_y *= y;
+    }
+
+    fn set_opacity(&mut self, opacity: f64) {
+        self.state.opacity = clamp_unit(opacity);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect, color: &Color) {
+        let color = color.to_rgba8();
+        let state = self.state;
+        let to_pixel_x = |x: f64| {
+            let pixel = f64::round(x * state.scale_x + state.translate_x);
+            return f64::min(f64::max(pixel, 0.0), self.image.width as f64) as u32;
+        };
+        let to_pixel_y = |y: f64| {
+            let pixel = f64::round(y * state.scale_y + state.translate_y);
+            return f64::min(f64::max(pixel, 0.0), self.image.height as f64) as u32;
+        };
+
+        let (min_x, max_x) = (to_pixel_x(rect.x), to_pixel_x(rect.x + rect.width));
+        let (min_y, max_y) = (to_pixel_y(rect.y), to_pixel_y(rect.y + rect.height));
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                self.blend_pixel(x, y, &color, 1.0);
+            }
+        }
+    }
+
+    fn fill_circle(&mut self, radius: f64, color: &Color) {
+        if radius <= 0.0 {
+            return;
+        }
+        let radius = radius * self.state.scale_x;
+        self.blend_radially(radius, color, |distance| {
+            clamp_unit(radius - distance + 0.5)
+        });
+    }
+
+    fn fill_polygons(&mut self, polygons: &Vec<Vec<Vector2>>, color: &Color) {
+        let color = color.to_rgba8();
+        let state = self.state;
+        let pixel_polygons: Vec<Vec<Vector2>> = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .iter()
+                    .map(|point| Vector2 {
+                        x: point.x * state.scale_x + state.translate_x,
+                        y: point.y * state.scale_y + state.translate_y,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut points = pixel_polygons.iter().flat_map(|polygon| polygon.iter());
+        let first = match points.next() {
+            Some(first) => *first,
+            None => return,
+        };
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+        for point in points {
+            min_x = f64::min(min_x, point.x);
+            min_y = f64::min(min_y, point.y);
+            max_x = f64::max(max_x, point.x);
+            max_y = f64::max(max_y, point.y);
+        }
+        let min_x = f64::max(f64::floor(min_x), 0.0) as u32;
+        let min_y = f64::max(f64::floor(min_y), 0.0) as u32;
+        let max_x = f64::min(f64::ceil(max_x), self.image.width as f64) as u32;
+        let max_y = f64::min(f64::ceil(max_y), self.image.height as f64) as u32;
+
+        let geometry = ShapeGeometry::Polygons(pixel_polygons);
+        let sample_count = POLYGON_SUPERSAMPLING * POLYGON_SUPERSAMPLING;
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let mut hits: u32 = 0;
+                for sample in 0..sample_count {
+                    let sample_x = x as f64
+                        + ((sample % POLYGON_SUPERSAMPLING) as f64 + 0.5)
+                            / POLYGON_SUPERSAMPLING as f64;
+                    let sample_y = y as f64
+                        + ((sample / POLYGON_SUPERSAMPLING) as f64 + 0.5)
+                            / POLYGON_SUPERSAMPLING as f64;
+                    if geometry.contains(sample_x, sample_y) {
+                        hits += 1;
+                    }
+                }
+                self.blend_pixel(x, y, &color, hits as f64 / sample_count as f64);
+            }
+        }
+    }
+
+    fn stroke_circle(&mut self, radius: f64, stroke_width: f64, color: &Color) {
+        if radius <= 0.0 || stroke_width <= 0.0 {
+            return;
+        }
+        let outer_radius = (radius + stroke_width / 2.0) * self.state.scale_x;
+        let inner_radius = (radius - stroke_width / 2.0) * self.state.scale_x;
+        self.blend_radially(outer_radius, color, |distance| {
+            clamp_unit(outer_radius - distance + 0.5) - clamp_unit(inner_radius - distance + 0.5)
+        });
+    }
+}
@@ -0,0 +1,111 @@
+use crate::color::ColorVision;
+use crate::document::{Document, DocumentError, LoadedDocument, MAX_DOCUMENT_BYTES};
+use miniz_oxide::inflate::TINFLStatus;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Deflate level for shared state. Links are written on every change,
+/// so this trades a little size for speed.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Why a shared link could not be read
+#[derive(Debug)]
+pub enum ShareError {
+    DecodeBase64(base64::DecodeError),
+    Decompress,
+    /// The decompressed bytes are not the packed state
+    Unpack(String),
+    Document(DocumentError),
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            ShareError::DecodeBase64(error) => write!(f, "invalid shared link: {}", error),
+            ShareError::Decompress => write!(f, "invalid shared link: could not decompress it"),
+            ShareError::Unpack(error) => write!(f, "invalid shared link: {}", error),
+            ShareError::Document(error) => write!(f, "invalid shared design: {}", error),
+        };
+    }
+}
+
+/// Everything a link carries. The design is a versioned document, so
+/// links keep working as the format changes.
+#[derive(Serialize)]
+struct SharedState<'a> {
+    document: &'a Document,
+    preview_vision: &'static str,
+}
+
+#[derive(Deserialize)]
+struct SharedStateSource {
+    document: Value,
+    preview_vision: Option<String>,
+}
+
+/// A design and editor settings restored from a link
+pub struct SharedDesign {
+    pub loaded: LoadedDocument,
+    pub preview_vision: ColorVision,
+}
+
+/// Packs the design into a url fragment: CBOR, deflated, then in url
+/// safe base64
+pub fn encode_share_fragment(document: &Document, preview_vision: &ColorVision) -> String {
+    let state = SharedState {
+        document: document,
+        preview_vision: preview_vision.get_name(),
+    };
+    let mut packed: Vec<u8> = vec![];
+    // writing plain structs to a vec can't fail
+    ciborium::ser::into_writer(&state, &mut packed).unwrap();
+
+    let compressed: Vec<u8> = miniz_oxide::deflate::compress_to_vec(&packed, COMPRESSION_LEVEL);
+    return base64::encode_config(&compressed, base64::URL_SAFE_NO_PAD);
+}
+
+/// Reads back a fragment made by `encode_share_fragment`, with or
+/// without its leading `#`
+pub fn decode_share_fragment(fragment: &str) -> Result<SharedDesign, ShareError> {
+    let fragment: &str = fragment.trim_start_matches('#');
+    let compressed: Vec<u8> = base64::decode_config(fragment, base64::URL_SAFE_NO_PAD)
+        .map_err(ShareError::DecodeBase64)?;
+    // packed state is smaller than the same document saved as json
+    let packed: Vec<u8> =
+        miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DOCUMENT_BYTES)
+            .map_err(|status| match status {
+                TINFLStatus::HasMoreOutput => ShareError::Document(DocumentError::TooLarge),
+                _ => ShareError::Decompress,
+            })?;
+    let state: SharedStateSource = ciborium::de::from_reader(packed.as_slice())
+        .map_err(|error| ShareError::Unpack(format!("{}", error)))?;
+
+    let loaded = Document::from_value(state.document).map_err(ShareError::Document)?;
+    let preview_vision: ColorVision = state
+        .preview_vision
+        .and_then(|name| ColorVision::from_name(&name))
+        .unwrap_or(ColorVision::Normal);
+    return Ok(SharedDesign {
+        loaded: loaded,
+        preview_vision: preview_vision,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_inflating_past_the_largest_document_are_rejected() {
+        let packed: Vec<u8> = vec![0; MAX_DOCUMENT_BYTES + 1];
+        let compressed: Vec<u8> = miniz_oxide::deflate::compress_to_vec(&packed, COMPRESSION_LEVEL);
+        let fragment: String = base64::encode_config(&compressed, base64::URL_SAFE_NO_PAD);
+
+        match decode_share_fragment(&fragment) {
+            Err(ShareError::Document(DocumentError::TooLarge)) => {}
+            Err(error) => panic!("expected the fragment to be too large, got {}", error),
+            Ok(_) => panic!("expected the fragment to be too large"),
+        };
+    }
+}
@@ -1,65 +1,96 @@
-use crate::drawing_style::DrawingStyle;
+use crate::action::{Action, DotId};
+use crate::canvas::web_canvas::paint_diagram_to_canvas;
+use crate::color::{Color, ColorVision};
+use crate::color_check::get_style_color_warnings;
+use crate::document::{Document, DocumentFormat};
+use crate::drawing_style::{ColorMode, DrawingStyle};
+use crate::error::AppError;
+use crate::fig::background::{Background, BackgroundShape};
 use crate::fig::diagram::Diagram;
 use crate::fig::dot::Dot;
-use crate::fig::text_path::ArcStyle;
-use crate::fig::text_path::TextPath;
+use crate::fig::shape::DotShape;
+use crate::fig::style_override::{ArcOverride, DotOverride, ResolvedArc, ResolvedDot, StyleSource};
+use crate::fig::text_path::{ArcStyle, TextPath};
+use crate::geom::{Length, LengthUnit};
+use crate::history::History;
+use crate::markup::to_markup;
+use crate::raster::encode::{encode_gif, encode_png, png_frame_file_name};
+use crate::raster::frames::{
+    frame_time, get_frame_height, render_frame, render_frames, FrameAnimation,
+};
+use crate::share::{decode_share_fragment, encode_share_fragment, SharedDesign};
+use crate::stl::emboss::{diagram_to_mesh, EmbossMode, EmbossOptions};
+use crate::svg::animation::{
+    animated_diagram_svg, AnimationEasing, AnimationKind, AnimationOptions,
+};
+use crate::svg::embedded_document::document_metadata_svg;
+use crate::svg::interactive::interactive_diagram_svg;
 use crate::svg::svg_drawable::SvgDrawable;
+use crate::svg::virtual_svg_drawable::VirtualSvgDrawable;
+use crate::themes::{built_in_presets, get_theme, THEME_NAMES};
+use serde::Serialize;
+use std::f64::consts::PI;
 use wasm_bindgen::prelude::*;
-// use wasm_bindgen::JsCast;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlCanvasElement;
 
 // use css_rs_macro::css;
 use virtual_dom_rs::*;
 
 #[derive(Debug)]
-struct FullApplicationState {
-    style: DrawingStyle,
-    diagram: Diagram,
+pub(crate) struct FullApplicationState {
+    pub(crate) style: DrawingStyle,
+    pub(crate) diagram: Diagram,
+    /// Color vision the editor previews the diagram with. Exports are
+    /// never affected.
+    pub(crate) preview_vision: ColorVision,
 }
 
-fn get_initial_state() -> FullApplicationState {
+pub(crate) fn get_initial_state() -> FullApplicationState {
     return FullApplicationState {
         style: DrawingStyle {
-            stroke_color: "#333333".to_string(),
-            background_color: "#EEEEEE".to_string(),
-            default_zero_dot_style: Dot {
-                circle_radius: 1.0,
-                ring_radius: 2.0,
-                ring_stroke_width: 0.1,
-            },
-            default_one_dot_style: Dot {
-                circle_radius: 0.5,
-                ring_radius: 2.0,
-                ring_stroke_width: 0.0,
-            },
-            default_arc_style: ArcStyle {
-                radius: 5.0,
-                arc_percentage: 1.0,
-                arc_offset_percentage: 0.0,
-            },
+            presets: built_in_presets(),
+            ..get_theme("light").unwrap()
         },
         diagram: Diagram {
-            diagram_padding: 5.0,
+            background: Background {
+                shape: BackgroundShape::Rectangle,
+                padding: Length {
+                    value: 2.0,
+                    unit: LengthUnit::Units,
+                },
+                center_image: None,
+            },
+            print_scale: 5.0,
+            expose_text: true,
             paths: vec![
                 TextPath {
                     text: "he".to_string(),
-                    zero_dot_style: None,
-                    one_dot_style: None,
-                    arc_style: None,
+                    preset: None,
+                    zero_dot_style: DotOverride::default(),
+                    one_dot_style: DotOverride::default(),
+                    arc_style: ArcOverride::default(),
+                    dot_overrides: vec![],
                 },
                 TextPath {
                     text: "ll".to_string(),
-                    zero_dot_style: None,
-                    one_dot_style: None,
-                    arc_style: None,
+                    preset: None,
+                    zero_dot_style: DotOverride::default(),
+                    one_dot_style: DotOverride::default(),
+                    arc_style: ArcOverride::default(),
+                    dot_overrides: vec![],
                 },
                 TextPath {
                     text: "o".to_string(),
-                    zero_dot_style: None,
-                    one_dot_style: None,
-                    arc_style: None,
+                    preset: None,
+                    zero_dot_style: DotOverride::default(),
+                    one_dot_style: DotOverride::default(),
+                    arc_style: ArcOverride::default(),
+                    dot_overrides: vec![],
                 },
             ],
         },
+        preview_vision: ColorVision::Normal,
     };
 }
 
@@ -67,18 +98,66 @@ trait StatelessComponent<T> {
     fn render(props: &T) -> VirtualNode;
 }
 
+/// Diagrams with more dots than this are drawn to a canvas instead of
+/// inline svg, which lags with thousands of elements.
+const CANVAS_DOT_THRESHOLD: usize = 2000;
+const DOTS_CANVAS_ID: &str = "dot-ring-canvas";
+
+fn should_render_to_canvas(state: &FullApplicationState) -> bool {
+    return state.diagram.get_dot_placements(&state.style).len() > CANVAS_DOT_THRESHOLD;
+}
+
+/// The diagram and style as the editor draws them, with the previewed
+/// color vision applied
+fn get_preview(state: &FullApplicationState) -> (Diagram, DrawingStyle) {
+    return (
+        state.diagram.simulate_vision(&state.preview_vision),
+        state.style.simulate_vision(&state.preview_vision),
+    );
+}
+
 struct DotsRenderer {}
 impl StatelessComponent<FullApplicationState> for DotsRenderer {
+    /// Renders either the inline svg, or an empty canvas that is
+    /// painted by `paint_dots_canvas` once it is in the document.
     fn render(props: &FullApplicationState) -> VirtualNode {
-        let raw_svg_string: String = props.diagram.as_svg(&props.style);
-        let img_base64_src: String = format!(
-            "data:image/svg+xml;base64,{}",
-            base64::encode_config(&raw_svg_string, base64::STANDARD)
-        );
+        if should_render_to_canvas(props) {
+            return html! {
+                <canvas class="dot-ring-img" id=DOTS_CANVAS_ID role="img">
+                    {props.diagram.get_accessible_title()}
+                </canvas>
+            };
+        }
+        let (diagram, style) = get_preview(props);
+        return diagram.as_virtual_svg(&style);
+    }
+}
 
-        return html! {
-            <img class="dot-ring-img" src=img_base64_src />
-        };
+fn paint_dots_canvas(state: &FullApplicationState) {
+    if !should_render_to_canvas(state) {
+        return;
+    }
+
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return,
+    };
+    let canvas_elem: Option<HtmlCanvasElement> = window
+        .document()
+        .and_then(|document| document.get_element_by_id(DOTS_CANVAS_ID))
+        .and_then(|elem| elem.dyn_into::<HtmlCanvasElement>().ok());
+
+    match canvas_elem {
+        Some(canvas_elem) => {
+            let (diagram, style) = get_preview(state);
+            if !paint_diagram_to_canvas(&canvas_elem, &diagram, &style, window.device_pixel_ratio())
+            {
+                web_sys::console::log_1(&"failed to get a 2d canvas context".into());
+            }
+        }
+        None => {
+            web_sys::console::log_1(&"no canvas to paint the diagram into".into());
+        }
     }
 }
 
@@ -86,23 +165,116 @@ struct ColorEditor {}
 struct ColorEditorProps<'a> {
     name: &'a str,
     color_id: &'a str,
-    value: &'a str,
+    value: &'a Color,
 }
 impl<'a> StatelessComponent<ColorEditorProps<'a>> for ColorEditor {
     fn render(props: &ColorEditorProps<'a>) -> VirtualNode {
         return html! {
             <form class="color-editor">
                 <label>{props.name}</label>
-                <input name={props.color_id} type="color" value={props.value}>
+                <input
+                    name={props.color_id}
+                    type="color"
+                    data_input_type="style_color"
+                    value={props.value.to_input_value()}>
             </form>
         };
     }
 }
 
+/// Names of the built in shapes offered by the shape picker
+const SHAPE_NAMES: [&str; 7] = [
+    "circle", "square", "diamond", "triangle", "star", "cross", "hollow",
+];
+
+struct DotShapeEditor {}
+impl<'a> StatelessComponent<DotProps<'a>> for DotShapeEditor {
+    fn render(props: &DotProps) -> VirtualNode {
+        let current_shape: &str = props.dot.shape.get_name();
+        let mut options: Vec<VirtualNode> = SHAPE_NAMES
+            .iter()
+            .map(|shape_name| {
+                if *shape_name == current_shape {
+                    html! { <option value=shape_name selected="selected">{shape_name.to_string()}</option> }
+                } else {
+                    html! { <option value=shape_name>{shape_name.to_string()}</option> }
+                }
+            })
+            .collect();
+        if let DotShape::Glyph(_) = props.dot.shape {
+            options.push(html! { <option value="glyph" selected="selected">custom glyph</option> });
+        }
+
+        let shape_parameters: VirtualNode = match props.dot.shape {
+            DotShape::Star {
+                points,
+                inner_radius_ratio,
+            } => html! {
+                <div class="dot-shape-parameters">
+                    <label>Star Points</label>
+                    <input
+                        name="star_points"
+                        type="number"
+                        step="1"
+                        min="2"
+                        max="24"
+                        data_input_type="dot_input"
+                        data_dot_id={props.dot_id}
+                        value={points} />
+                    <label>Star Inner Radius</label>
+                    <input
+                        name="star_inner_radius_ratio"
+                        type="range"
+                        step="0.05"
+                        min="0.0"
+                        max="1.0"
+                        data_input_type="dot_input"
+                        data_dot_id={props.dot_id}
+                        value={inner_radius_ratio} />
+                </div>
+            },
+            DotShape::Cross { arm_width_ratio } => html! {
+                <div class="dot-shape-parameters">
+                    <label>Cross Arm Width</label>
+                    <input
+                        name="cross_arm_width_ratio"
+                        type="range"
+                        step="0.05"
+                        min="0.0"
+                        max="2.0"
+                        data_input_type="dot_input"
+                        data_dot_id={props.dot_id}
+                        value={arm_width_ratio} />
+                </div>
+            },
+            _ => html! { <div class="dot-shape-parameters"></div> },
+        };
+
+        return html! {
+            <div class="dot-shape-editor">
+                <label>Shape</label>
+                <select name="shape" data_input_type="dot_shape" data_dot_id={props.dot_id}>
+                    {options}
+                </select>
+                {shape_parameters}
+                <label>Custom Glyph</label>
+                <input
+                    name="glyph"
+                    type="file"
+                    accept=".svg,image/svg+xml,text/plain"
+                    data_input_type="dot_glyph"
+                    data_dot_id={props.dot_id} />
+            </div>
+        };
+    }
+}
+
 struct DotEditor {}
 struct DotProps<'a> {
     dot: &'a Dot,
     dot_id: &'a str,
+    /// Shown for colors the dot inherits from the style
+    stroke_color: &'a Color,
 }
 
 impl<'a> StatelessComponent<DotProps<'a>> for DotEditor {
@@ -112,6 +284,8 @@ impl<'a> StatelessComponent<DotProps<'a>> for DotEditor {
     fn render(props: &DotProps) -> VirtualNode {
         return html! {
             <form class="dot-editor">
+                {DotShapeEditor::render(props)}
+
                 <label>Circle Radius</label>
                 <input
                     name="circle_radius"
@@ -150,7 +324,7 @@ impl<'a> StatelessComponent<DotProps<'a>> for DotEditor {
                     max="10.0"
                     data_input_type="dot_input"
                     data_dot_id={props.dot_id}
-                    value={props.dot.circle_radius} />
+                    value={props.dot.ring_radius} />
 
                 <label>Ring Stroke Width</label>
                 <input
@@ -170,7 +344,76 @@ impl<'a> StatelessComponent<DotProps<'a>> for DotEditor {
                     max="10.0"
                     data_input_type="dot_input"
                     data_dot_id={props.dot_id}
-                    value={props.dot.circle_radius} />
+                    value={props.dot.ring_stroke_width} />
+
+                <label>Fill Color</label>
+                <input
+                    name="fill_color"
+                    type="color"
+                    data_input_type="dot_color"
+                    data_dot_id={props.dot_id}
+                    value={props.dot.get_fill_color(props.stroke_color).to_input_value()} />
+
+                <label>Ring Color</label>
+                <input
+                    name="ring_color"
+                    type="color"
+                    data_input_type="dot_color"
+                    data_dot_id={props.dot_id}
+                    value={props.dot.get_ring_color(props.stroke_color).to_input_value()} />
+
+                <label>Opacity</label>
+                <input
+                    name="opacity"
+                    type="range"
+                    step="0.05"
+                    min="0.0"
+                    max="1.0"
+                    data_input_type="dot_input"
+                    data_dot_id={props.dot_id}
+                    value={props.dot.get_opacity()} />
+            </form>
+        };
+    }
+}
+
+/// Names of the color modes offered by the color mode picker
+const COLOR_MODE_NAMES: [&str; 4] = ["single", "ring_palette", "arc_gradient", "radial_gradient"];
+
+struct ColorModeEditor {}
+impl StatelessComponent<ColorMode> for ColorModeEditor {
+    fn render(props: &ColorMode) -> VirtualNode {
+        let current_mode: &str = props.get_name();
+        let options: Vec<VirtualNode> = COLOR_MODE_NAMES
+            .iter()
+            .map(|mode_name| {
+                if *mode_name == current_mode {
+                    html! { <option value=mode_name selected="selected">{mode_name.to_string()}</option> }
+                } else {
+                    html! { <option value=mode_name>{mode_name.to_string()}</option> }
+                }
+            })
+            .collect();
+        let colors: Vec<String> = props
+            .get_colors()
+            .iter()
+            .map(|color| color.to_css())
+            .collect();
+        let colors: String = colors.join(", ");
+
+        return html! {
+            <form class="color-mode-editor">
+                <label>Color Mode</label>
+                <select name="color_mode" data_input_type="color_mode">
+                    {options}
+                </select>
+                <label>Mode Colors</label>
+                <input
+                    name="color_mode_colors"
+                    type="text"
+                    placeholder="#d62828, #003049"
+                    data_input_type="color_mode"
+                    value=colors />
             </form>
         };
     }
@@ -184,18 +427,26 @@ impl StatelessComponent<DrawingStyle> for StyleEditor {
                 <h3 class="style-editor-subheader">Default One Dot</h3>
                 {DotEditor::render(&DotProps {
                     dot: &props.default_one_dot_style,
-                    dot_id: "default-one-dot"
+                    dot_id: "default-one-dot",
+                    stroke_color: &props.stroke_color,
                 })}
 
                 <h3 class="style-editor-subheader">Default Zero Dot</h3>
                 {DotEditor::render(&DotProps {
                     dot: &props.default_zero_dot_style,
-                    dot_id: "default-zero-dot"
+                    dot_id: "default-zero-dot",
+                    stroke_color: &props.stroke_color,
+                })}
+
+                <h3 class="style-editor-subheader">Default Arc</h3>
+                {ArcEditor::render(&ArcProps {
+                    arc_style: &props.default_arc_style,
+                    ring_index: None,
                 })}
 
                 {ColorEditor::render(&ColorEditorProps {
                     name: "Stroke Color",
-                    color_id: "background_color",
+                    color_id: "stroke_color",
                     value: &props.stroke_color,
                 })}
 
@@ -204,6 +455,8 @@ impl StatelessComponent<DrawingStyle> for StyleEditor {
                     color_id: "background_color",
                     value: &props.background_color,
                 })}
+
+                {ColorModeEditor::render(&props.color_mode)}
             </div>
         };
     }
@@ -219,7 +472,493 @@ impl RingTextArea {
 impl StatelessComponent<Vec<TextPath>> for RingTextArea {
     fn render(props: &Vec<TextPath>) -> VirtualNode {
         return html! {
-            <textarea class="ring-text-area">{RingTextArea::get_as_multiline_text(props)}</textarea>
+            <textarea
+                class="ring-text-area"
+                name="ring_text"
+                data_input_type="ring_text">{RingTextArea::get_as_multiline_text(props)}</textarea>
+        };
+    }
+}
+
+struct ExposeTextToggle {}
+impl StatelessComponent<Diagram> for ExposeTextToggle {
+    fn render(props: &Diagram) -> VirtualNode {
+        let checkbox = if props.expose_text {
+            html! {
+                <input
+                    name="expose_text"
+                    type="checkbox"
+                    data_input_type="expose_text"
+                    checked="checked" />
+            }
+        } else {
+            html! {
+                <input
+                    name="expose_text"
+                    type="checkbox"
+                    data_input_type="expose_text" />
+            }
+        };
+
+        return html! {
+            <form class="expose-text-toggle">
+                <label>Describe text to screen readers</label>
+                {checkbox}
+            </form>
+        };
+    }
+}
+
+/// Names of the background shapes offered by the background picker
+const BACKGROUND_SHAPE_NAMES: [&str; 4] = ["none", "rectangle", "rounded_rectangle", "circle"];
+const LENGTH_UNIT_NAMES: [&str; 3] = ["units", "mm", "in"];
+
+/// Options for a select, with `current` selected
+fn select_options(names: &[&str], current: &str) -> Vec<VirtualNode> {
+    return names
+        .iter()
+        .map(|name| {
+            if *name == current {
+                html! { <option value=name selected="selected">{name.to_string()}</option> }
+            } else {
+                html! { <option value=name>{name.to_string()}</option> }
+            }
+        })
+        .collect();
+}
+
+struct BackgroundEditor {}
+impl StatelessComponent<Diagram> for BackgroundEditor {
+    fn render(props: &Diagram) -> VirtualNode {
+        let corner_radius: f64 = match props.background.shape {
+            BackgroundShape::RoundedRectangle { corner_radius } => corner_radius,
+            _ => 0.0,
+        };
+
+        return html! {
+            <form class="background-editor">
+                <label>Background</label>
+                <select name="background_shape" data_input_type="background">
+                    {select_options(&BACKGROUND_SHAPE_NAMES, props.background.shape.get_name())}
+                </select>
+                <label>Corner Radius</label>
+                <input
+                    name="corner_radius"
+                    type="number"
+                    step="0.1"
+                    min="0.0"
+                    data_input_type="background"
+                    value={corner_radius} />
+                <label>Padding</label>
+                <input
+                    name="padding"
+                    type="number"
+                    step="0.5"
+                    min="0.0"
+                    data_input_type="background"
+                    value={props.background.padding.value} />
+                <select name="padding_unit" data_input_type="background">
+                    {select_options(&LENGTH_UNIT_NAMES, props.background.padding.unit.get_name())}
+                </select>
+                <label>Print Scale (mm per unit)</label>
+                <input
+                    name="print_scale"
+                    type="number"
+                    step="0.5"
+                    min="0.1"
+                    data_input_type="background"
+                    value={props.print_scale} />
+                <label>Center Image</label>
+                <input
+                    name="center_image"
+                    type="file"
+                    accept="image/*"
+                    data_input_type="center_image" />
+            </form>
+        };
+    }
+}
+
+/// Points on the arc dial, which has a radius of 1 and turns the same
+/// way as the diagram, starting from the right
+fn get_dial_point(turns: f64) -> (f64, f64) {
+    let angle: f64 = turns * 2.0 * PI;
+    return (angle.cos(), angle.sin());
+}
+
+/// A dial showing where an arc starts and how far it sweeps. Its two
+/// handles are dragged from js, which sends `set_arc_dial` for the start
+/// and `set_arc_value` for the end.
+fn arc_dial(arc_style: &ArcStyle, ring_index: &str) -> VirtualNode {
+    let start_turns: f64 = arc_style.arc_offset_percentage;
+    // a full sweep would start and end on the same point, which svg
+    // draws as no arc at all
+    let end_turns: f64 = start_turns + arc_style.arc_percentage.min(0.9999);
+    let (start_x, start_y) = get_dial_point(start_turns);
+    let (end_x, end_y) = get_dial_point(end_turns);
+    let large_arc: u8 = if arc_style.arc_percentage > 0.5 { 1 } else { 0 };
+    let sweep_path: String = format!(
+        "M {} {} A 1 1 0 {} 1 {} {}",
+        start_x, start_y, large_arc, end_x, end_y
+    );
+
+    return html! {
+        <svg
+            class="arc-dial"
+            xmlns="http://www.w3.org/2000/svg"
+            viewBox="-1.25 -1.25 2.5 2.5"
+            data_ring_index={ring_index}
+            data_arc_offset_percentage={arc_style.arc_offset_percentage}
+            data_arc_percentage={arc_style.arc_percentage}>
+            <circle class="arc-dial-track" r="1" />
+            <path class="arc-dial-sweep" d=sweep_path />
+            <circle class="arc-dial-handle" data_dial_handle="start" cx=start_x cy=start_y r="0.15" />
+            <circle class="arc-dial-handle" data_dial_handle="end" cx=end_x cy=end_y r="0.15" />
+        </svg>
+    };
+}
+
+struct ArcEditor {}
+struct ArcProps<'a> {
+    arc_style: &'a ArcStyle,
+    /// The ring whose override is edited, or None for the default arc
+    ring_index: Option<usize>,
+}
+
+impl<'a> StatelessComponent<ArcProps<'a>> for ArcEditor {
+    fn render(props: &ArcProps) -> VirtualNode {
+        let ring_index: String = match props.ring_index {
+            Some(ring_index) => ring_index.to_string(),
+            None => "".to_string(),
+        };
+
+        return html! {
+            <form class="arc-editor">
+                {arc_dial(props.arc_style, &ring_index)}
+
+                <label>Arc Radius</label>
+                <input
+                    name="radius"
+                    type="range"
+                    step="0.5"
+                    min="0.5"
+                    max="30.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index.clone()}
+                    value={props.arc_style.radius} />
+                <input
+                    name="radius"
+                    type="number"
+                    step="0.5"
+                    min="0.5"
+                    max="30.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index.clone()}
+                    value={props.arc_style.radius} />
+
+                <label>Arc Sweep</label>
+                <input
+                    name="arc_percentage"
+                    type="range"
+                    step="0.01"
+                    min="0.0"
+                    max="1.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index.clone()}
+                    value={props.arc_style.arc_percentage} />
+                <input
+                    name="arc_percentage"
+                    type="number"
+                    step="0.01"
+                    min="0.0"
+                    max="1.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index.clone()}
+                    value={props.arc_style.arc_percentage} />
+
+                <label>Arc Offset</label>
+                <input
+                    name="arc_offset_percentage"
+                    type="range"
+                    step="0.01"
+                    min="0.0"
+                    max="1.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index.clone()}
+                    value={props.arc_style.arc_offset_percentage} />
+                <input
+                    name="arc_offset_percentage"
+                    type="number"
+                    step="0.01"
+                    min="0.0"
+                    max="1.0"
+                    data_input_type="arc_input"
+                    data_ring_index={ring_index}
+                    value={props.arc_style.arc_offset_percentage} />
+            </form>
+        };
+    }
+}
+
+fn get_dot_sources(dot: &ResolvedDot) -> Vec<(&'static str, &StyleSource)> {
+    return vec![
+        ("shape", &dot.shape.source),
+        ("circle radius", &dot.circle_radius.source),
+        ("ring radius", &dot.ring_radius.source),
+        ("ring stroke width", &dot.ring_stroke_width.source),
+        ("fill color", &dot.fill_color.source),
+        ("ring color", &dot.ring_color.source),
+        ("opacity", &dot.opacity.source),
+    ];
+}
+
+fn get_arc_sources(arc: &ResolvedArc) -> Vec<(&'static str, &StyleSource)> {
+    return vec![
+        ("radius", &arc.radius.source),
+        ("sweep", &arc.arc_percentage.source),
+        ("offset", &arc.arc_offset_percentage.source),
+    ];
+}
+
+/// Says where a ring's style comes from: what it inherits the whole
+/// style from, or which fields an override still leaves inherited
+fn describe_sources(sources: &[(&'static str, &StyleSource)], overridden: bool) -> String {
+    if !overridden {
+        return match sources.iter().find(|(_, source)| source.is_overridden()) {
+            Some((_, source)) => format!("Inherited from {}", source.get_description()),
+            None => "Inherited from the defaults".to_string(),
+        };
+    }
+
+    let inherited: Vec<String> = sources
+        .iter()
+        .filter(|(_, source)| **source != StyleSource::Ring)
+        .map(|(name, source)| format!("{} from {}", name, source.get_description()))
+        .collect();
+    if inherited.is_empty() {
+        return "".to_string();
+    }
+    return format!("Still inherits {}", inherited.join(", "));
+}
+
+fn override_toggle(label: &str, part: &str, ring_index: usize, overridden: bool) -> VirtualNode {
+    let checkbox = if overridden {
+        html! {
+            <input
+                name=part
+                type="checkbox"
+                data_input_type="ring_override"
+                data_ring_index={ring_index}
+                checked="checked" />
+        }
+    } else {
+        html! {
+            <input
+                name=part
+                type="checkbox"
+                data_input_type="ring_override"
+                data_ring_index={ring_index} />
+        }
+    };
+
+    return html! {
+        <form class="ring-override-toggle">
+            <label>{label}</label>
+            {checkbox}
+        </form>
+    };
+}
+
+struct RingEditor {}
+struct RingProps<'a> {
+    path: &'a TextPath,
+    ring_index: usize,
+    style: &'a DrawingStyle,
+}
+
+impl RingEditor {
+    /// The toggle for overriding one of the ring's dot styles, followed
+    /// by an editor for it if it is overridden
+    fn render_dot_override(props: &RingProps, value: bool) -> Vec<VirtualNode> {
+        let (label, part, dot_override) = if value {
+            (
+                "Override One Dots",
+                "one_dot_style",
+                &props.path.one_dot_style,
+            )
+        } else {
+            (
+                "Override Zero Dots",
+                "zero_dot_style",
+                &props.path.zero_dot_style,
+            )
+        };
+        let overridden: bool = !dot_override.is_empty();
+        let resolved: ResolvedDot = props.path.resolve_dot_style(props.style, value, None);
+        let sources: String = describe_sources(&get_dot_sources(&resolved), overridden);
+
+        let mut nodes: Vec<VirtualNode> = vec![
+            override_toggle(label, part, props.ring_index, overridden),
+            html! { <p class="style-source">{sources}</p> },
+        ];
+        if overridden {
+            let dot_id: String = DotId::Ring {
+                ring_index: props.ring_index,
+                value: value,
+            }
+            .get_name();
+            nodes.push(DotEditor::render(&DotProps {
+                dot: &resolved.to_dot(),
+                dot_id: &dot_id,
+                stroke_color: &props.style.stroke_color,
+            }));
+        }
+        return nodes;
+    }
+
+    fn render_arc_override(props: &RingProps) -> Vec<VirtualNode> {
+        let overridden: bool = !props.path.arc_style.is_empty();
+        let resolved: ResolvedArc = props.path.resolve_arc_style(props.style);
+        let sources: String = describe_sources(&get_arc_sources(&resolved), overridden);
+
+        let mut nodes: Vec<VirtualNode> = vec![
+            override_toggle("Override Arc", "arc_style", props.ring_index, overridden),
+            html! { <p class="style-source">{sources}</p> },
+        ];
+        if overridden {
+            nodes.push(ArcEditor::render(&ArcProps {
+                arc_style: &resolved.to_arc_style(),
+                ring_index: Some(props.ring_index),
+            }));
+        }
+        return nodes;
+    }
+}
+
+impl<'a> StatelessComponent<RingProps<'a>> for RingEditor {
+    fn render(props: &RingProps<'a>) -> VirtualNode {
+        let mut preset_names: Vec<&str> = vec![""];
+        preset_names.extend(
+            props
+                .style
+                .presets
+                .iter()
+                .map(|preset| preset.name.as_str()),
+        );
+        let current_preset: &str = match &props.path.preset {
+            Some(preset_name) => preset_name,
+            None => "",
+        };
+
+        return html! {
+            <div class="ring-editor">
+                <h3 class="style-editor-subheader">{format!("Ring {}", props.ring_index + 1)}</h3>
+                <form class="ring-settings">
+                    <label>Text</label>
+                    <input
+                        name="ring_text"
+                        type="text"
+                        data_input_type="ring_line"
+                        data_ring_index={props.ring_index}
+                        value={props.path.text.clone()} />
+                    <label>Preset</label>
+                    <select
+                        name="ring_preset"
+                        data_input_type="ring_preset"
+                        data_ring_index={props.ring_index}>
+                        {select_options(&preset_names, current_preset)}
+                    </select>
+                </form>
+                {RingEditor::render_dot_override(props, true)}
+                {RingEditor::render_dot_override(props, false)}
+                {RingEditor::render_arc_override(props)}
+            </div>
+        };
+    }
+}
+
+struct RingEditors {}
+impl StatelessComponent<FullApplicationState> for RingEditors {
+    fn render(props: &FullApplicationState) -> VirtualNode {
+        let ring_editors: Vec<VirtualNode> = props
+            .diagram
+            .paths
+            .iter()
+            .enumerate()
+            .map(|(ring_index, path)| {
+                RingEditor::render(&RingProps {
+                    path: path,
+                    ring_index: ring_index,
+                    style: &props.style,
+                })
+            })
+            .collect();
+
+        return html! {
+            <div class="ring-editors">
+                {ring_editors}
+            </div>
+        };
+    }
+}
+
+struct PresetEditor {}
+impl StatelessComponent<FullApplicationState> for PresetEditor {
+    fn render(_props: &FullApplicationState) -> VirtualNode {
+        return html! {
+            <form class="preset-editor">
+                <label>Theme</label>
+                <select name="theme" data_input_type="theme">
+                    <option value="" selected="selected">choose a theme</option>
+                    {select_options(&THEME_NAMES, "")}
+                </select>
+                <label>Save Default Dots As Preset</label>
+                <input
+                    name="preset_name"
+                    type="text"
+                    placeholder="preset name"
+                    data_input_type="preset_name" />
+            </form>
+        };
+    }
+}
+
+/// Names of the color visions offered by the preview picker
+const COLOR_VISION_NAMES: [&str; 4] = ["normal", "protanopia", "deuteranopia", "tritanopia"];
+
+struct ColorCheckPanel {}
+impl StatelessComponent<FullApplicationState> for ColorCheckPanel {
+    fn render(props: &FullApplicationState) -> VirtualNode {
+        let current_vision: &str = props.preview_vision.get_name();
+        let options: Vec<VirtualNode> = COLOR_VISION_NAMES
+            .iter()
+            .map(|vision_name| {
+                if *vision_name == current_vision {
+                    html! { <option value=vision_name selected="selected">{vision_name.to_string()}</option> }
+                } else {
+                    html! { <option value=vision_name>{vision_name.to_string()}</option> }
+                }
+            })
+            .collect();
+
+        let mut warnings: Vec<VirtualNode> = get_style_color_warnings(&props.style)
+            .into_iter()
+            .map(|warning| html! { <li>{warning}</li> })
+            .collect();
+        if warnings.is_empty() {
+            warnings.push(html! { <li>No color problems found</li> });
+        }
+
+        return html! {
+            <div class="color-check">
+                <form class="color-vision-preview">
+                    <label>Preview Color Vision</label>
+                    <select name="color_vision" data_input_type="color_vision">
+                        {options}
+                    </select>
+                </form>
+                <ul class="color-warnings">{warnings}</ul>
+            </div>
         };
     }
 }
@@ -229,10 +968,27 @@ impl StatelessComponent<FullApplicationState> for Controls {
     fn render(props: &FullApplicationState) -> VirtualNode {
         return html! {
             <div class="control-bar">
+                <form class="open-design">
+                    <label>Open Design</label>
+                    <input
+                        name="open_design"
+                        type="file"
+                        accept=".svg,image/svg+xml,.json,.toml"
+                        data_input_type="open_design" />
+                </form>
                 {RingTextArea::render(&props.diagram.paths)}
+                {ExposeTextToggle::render(&props.diagram)}
+                <hr class="controls-divider" />
+                {BackgroundEditor::render(&props.diagram)}
                 <hr class="controls-divider" />
                 {StyleEditor::render(&props.style)}
                 <hr class="controls-divider" />
+                {PresetEditor::render(props)}
+                <hr class="controls-divider" />
+                {RingEditors::render(props)}
+                <hr class="controls-divider" />
+                {ColorCheckPanel::render(props)}
+                <hr class="controls-divider" />
             </div>
         };
     }
@@ -241,7 +997,10 @@ impl StatelessComponent<FullApplicationState> for Controls {
 struct ApplicationRenderer {}
 impl StatelessComponent<FullApplicationState> for ApplicationRenderer {
     fn render(props: &FullApplicationState) -> VirtualNode {
-        let background_style = format!("background-color: {}", props.style.background_color);
+        let background_style = format!(
+            "background-color: {}",
+            props.style.background_color.to_css()
+        );
         return html! {
             <div class="app-split" style=background_style>
                 {DotsRenderer::render(props)}
@@ -255,126 +1014,655 @@ impl StatelessComponent<FullApplicationState> for ApplicationRenderer {
 #[derive(Debug)]
 pub struct AppMount {
     dom: VirtualNode,
+    /// The diagram as it was last written into the page
+    diagram_markup: String,
 }
 
 #[wasm_bindgen]
 #[derive(Debug)]
 pub struct App {
     state: FullApplicationState,
+    history: History<DesignSnapshot>,
     mount_state: Option<AppMount>,
 }
 
-/// Create a new app and return it
+/// The parts of the state that edits change and undo restores. The
+/// preview vision is left out, as it's a setting of the editor rather
+/// than part of the design.
+#[derive(Debug)]
+struct DesignSnapshot {
+    style: DrawingStyle,
+    diagram: Diagram,
+}
+
+impl DesignSnapshot {
+    fn take(state: &FullApplicationState) -> DesignSnapshot {
+        return DesignSnapshot {
+            style: state.style.clone(),
+            diagram: state.diagram.clone(),
+        };
+    }
+
+    fn restore(self, state: &mut FullApplicationState) {
+        state.style = self.style;
+        state.diagram = self.diagram;
+    }
+}
+
+/// Applies an action to the app. Design edits record the design as it
+/// was before, so they can be undone.
+fn apply_action(app: &mut App, action: Action) -> Result<Vec<String>, AppError> {
+    if !action.is_design_edit() {
+        return action.apply(&mut app.state);
+    }
+    let edit_key: Option<String> = action.get_edit_key();
+    let before: DesignSnapshot = DesignSnapshot::take(&app.state);
+    let warnings: Vec<String> = action.apply(&mut app.state)?;
+    app.history.record(before, edit_key);
+    return Ok(warnings);
+}
+
+/// The page's url fragment without its `#`, if it has one
+fn get_location_hash() -> Option<String> {
+    let hash: String = web_sys::window()?.location().hash().ok()?;
+    let hash: &str = hash.trim_start_matches('#');
+    if hash.is_empty() {
+        return None;
+    }
+    return Some(hash.to_string());
+}
+
+fn get_share_fragment(state: &FullApplicationState) -> String {
+    return encode_share_fragment(
+        &Document::new(&state.style, &state.diagram),
+        &state.preview_vision,
+    );
+}
+
+/// Keeps the url pointing at the current design, so it can be copied
+/// and sent to someone else. The history entry is replaced rather than
+/// pushed, so dragging a slider doesn't flood the back button.
+fn write_location_hash(state: &FullApplicationState) {
+    let history = match web_sys::window().and_then(|window| window.history().ok()) {
+        Some(history) => history,
+        None => return,
+    };
+    let url: String = format!("#{}", get_share_fragment(state));
+    if history
+        .replace_state_with_url(&JsValue::NULL, "", Some(&url))
+        .is_err()
+    {
+        web_sys::console::log_1(&"failed to write the design into the url".into());
+    }
+}
+
+/// Replaces the design and editor settings with shared ones, returning
+/// any warnings from loading the design
+fn restore_shared_design(state: &mut FullApplicationState, shared: SharedDesign) -> Vec<String> {
+    state.style = shared.loaded.document.style;
+    state.diagram = shared.loaded.document.diagram;
+    state.preview_vision = shared.preview_vision;
+    return shared.loaded.warnings;
+}
+
+/// The state shared in the page's url, or the initial state if there
+/// is none or it can't be read
+fn get_state_from_location() -> FullApplicationState {
+    let mut state: FullApplicationState = get_initial_state();
+    let fragment: String = match get_location_hash() {
+        Some(fragment) => fragment,
+        None => return state,
+    };
+    match decode_share_fragment(&fragment) {
+        Ok(shared) => {
+            for warning in restore_shared_design(&mut state, shared).iter() {
+                web_sys::console::log_1(&warning.into());
+            }
+        }
+        Err(error) => {
+            web_sys::console::log_1(&format!("{}", error).into());
+        }
+    }
+    return state;
+}
+
+/// Create a new app and return it, restoring the design shared in the
+/// page's url if there is one
 #[wasm_bindgen]
 #[allow(dead_code)]
 pub fn init_app() -> App {
     return App {
-        state: get_initial_state(),
+        state: get_state_from_location(),
+        history: History::new(),
         mount_state: None,
     };
 }
 
-/// Mounts the app at the given seelctor.
+/// The diagram's svg, or the canvas it's painted on, as markup. It's
+/// the first child of the app's root element.
+fn get_diagram_markup(app_dom: &VirtualNode) -> String {
+    return match app_dom
+        .as_velement_ref()
+        .and_then(|root| root.children.first())
+    {
+        Some(diagram) => to_markup(diagram),
+        None => "".to_string(),
+    };
+}
+
+/// Writes the diagram into the page again from markup.
+///
+/// virtual-dom-rs creates elements with `createElement`, which puts new
+/// svg elements in the html namespace, where they aren't drawn. Markup
+/// is parsed into the svg namespace, so the diagram is written as markup
+/// whenever it changes instead of being left as patched.
+fn replace_diagram(host_elem: &Element, diagram_markup: &str) -> Result<(), AppError> {
+    return match host_elem.query_selector(".dot-ring-img") {
+        Ok(Some(diagram_elem)) => {
+            diagram_elem.set_outer_html(diagram_markup);
+            Ok(())
+        }
+        _ => Err(AppError::Patch(
+            "the diagram is missing from the page".to_string(),
+        )),
+    };
+}
+
+/// Mounts the app in the given element.
 ///
-/// Returns true iff the app mounted incorrectly
-/// Will throw if no element with an appropriate selector is found
-/// or if the provided app already has a dom.
+/// Throws an `AppError` if the provided app already has a dom.
 #[wasm_bindgen]
 #[allow(dead_code)]
-pub fn mount(host_elem: Element, app: &mut App) -> bool {
+pub fn mount(host_elem: Element, app: &mut App) -> Result<(), JsValue> {
     match &app.mount_state {
         Some(_x) => {
-            return false;
+            return Err(AppError::AlreadyMounted.into());
         }
         None => {
             let next_dom = ApplicationRenderer::render(&app.state);
-            host_elem.set_inner_html(&next_dom.to_string());
-            app.mount_state = Some(AppMount { dom: next_dom });
-            return true;
+            host_elem.set_inner_html(&to_markup(&next_dom));
+            app.mount_state = Some(AppMount {
+                diagram_markup: get_diagram_markup(&next_dom),
+                dom: next_dom,
+            });
+            paint_dots_canvas(&app.state);
+            return Ok(());
         }
     };
 }
 
+/// Exports the current diagram as a standalone svg document
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_svg(app: &App) -> String {
+    return app.state.diagram.as_svg(&app.state.style);
+}
+
+/// Exports the current diagram as an svg with the design embedded in
+/// its metadata, so it can be opened in the editor again.
+///
+/// The embedded design includes the text even when it isn't described
+/// to screen readers, so this shouldn't be used for secret designs.
+#[wasm_bindgen]
 #[allow(dead_code)]
+pub fn export_editable_svg(app: &App) -> Result<String, JsValue> {
+    let document = Document::new(&app.state.style, &app.state.diagram);
+    let metadata: String =
+        document_metadata_svg(&document).map_err(|error| AppError::Encoding {
+            format: "svg",
+            reason: format!("{}", error),
+        })?;
+    return Ok(app
+        .state
+        .diagram
+        .as_svg_with_metadata(&app.state.style, &metadata));
+}
+
+/// Exports the current diagram as an svg with per-dot tooltips and
+/// hover highlighting, for exploring the encoding in a browser
 #[wasm_bindgen]
-pub fn action_update_default_dot(
-    app: &mut App,
-    dot_id: &str,
-    input_name: &str,
-    new_value: f64,
-) -> bool {
-    web_sys::console::log_1(
-        &format!(
-            "rust called with {:?} {:?} {:?}",
-            &dot_id, input_name, new_value
+#[allow(dead_code)]
+pub fn export_interactive_svg(app: &App) -> String {
+    return interactive_diagram_svg(&app.state.diagram, &app.state.style);
+}
+
+/// Exports the current diagram as a looping animated svg.
+///
+/// `kind` is one of "appear", "rotate" or "sweep", and `easing` one of
+/// "linear", "ease-in", "ease-out", "ease-in-out" or a css
+/// `cubic-bezier(x1, y1, x2, y2)` with every point within 0 to 1. Throws
+/// an `AppError` if either is not recognised, or if the duration is not
+/// a positive number of seconds.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_animated_svg(
+    app: &App,
+    kind: &str,
+    duration_seconds: f64,
+    easing: &str,
+) -> Result<String, JsValue> {
+    if !(duration_seconds.is_finite() && duration_seconds > 0.0) {
+        return Err(AppError::invalid_value(
+            "duration_seconds",
+            "must be a positive number of seconds",
         )
-        .into(),
-    );
+        .into());
+    }
+    let options = AnimationOptions {
+        kind: AnimationKind::from_name(kind)
+            .ok_or_else(|| AppError::unknown_id("animation", kind))?,
+        duration_seconds: duration_seconds,
+        easing: AnimationEasing::from_name(easing)
+            .ok_or_else(|| AppError::unknown_id("easing", easing))?,
+    };
+
+    return Ok(animated_diagram_svg(
+        &app.state.diagram,
+        &app.state.style,
+        &options,
+    ));
+}
 
-    let default_dot: &mut Dot = match dot_id.as_ref() {
-        "default-one-dot" => &mut app.state.style.default_one_dot_style,
-        "default-zero-dot" => &mut app.state.style.default_zero_dot_style,
+fn get_frame_animation(name: &str) -> Result<FrameAnimation, AppError> {
+    return FrameAnimation::from_name(name).ok_or_else(|| AppError::unknown_id("animation", name));
+}
+
+/// Widest raster export, in pixels. Frames are held in memory while they
+/// are encoded, so much larger ones would run out of it.
+const MAX_FRAME_WIDTH: u32 = 4096;
+/// Fastest gif frame rate, since gif delays are in hundredths of a second
+const MAX_GIF_FRAMES_PER_SECOND: f64 = 100.0;
+/// Most pixels across all the frames of a gif. Every frame is held in
+/// memory until the gif is encoded, at four bytes a pixel.
+const MAX_GIF_PIXELS: u64 = 1 << 25;
+
+fn check_frames(frame_count: u32, width: u32) -> Result<(), AppError> {
+    if frame_count == 0 {
+        return Err(AppError::invalid_value("frame_count", "must be at least 1"));
+    }
+    if width == 0 || width > MAX_FRAME_WIDTH {
+        return Err(AppError::invalid_value(
+            "width",
+            &format!("must be between 1 and {} pixels", MAX_FRAME_WIDTH),
+        ));
+    }
+    return Ok(());
+}
+
+fn frames_too_large() -> AppError {
+    return AppError::invalid_value("width", "is too large to render");
+}
+
+/// Exports an animation of the diagram as a looping gif.
+///
+/// `animation` is one of "type-out" or "rotate". Throws an `AppError`
+/// if the animation is not recognised, if there are no frames, the width
+/// is not between 1 and 4096 pixels, the frames add up to more than
+/// 2^25 pixels, the frame rate is not above 0 and at most 100, or if the
+/// gif could not be encoded.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_gif(
+    app: &App,
+    animation: &str,
+    frame_count: u32,
+    frames_per_second: f64,
+    width: u32,
+) -> Result<Vec<u8>, JsValue> {
+    let animation = get_frame_animation(animation)?;
+    check_frames(frame_count, width)?;
+    if !(frames_per_second > 0.0 && frames_per_second <= MAX_GIF_FRAMES_PER_SECOND) {
+        return Err(AppError::invalid_value(
+            "frames_per_second",
+            &format!("must be above 0 and at most {}", MAX_GIF_FRAMES_PER_SECOND),
+        )
+        .into());
+    }
+    let height: u32 = get_frame_height(&app.state.diagram, &app.state.style, width);
+    let pixels: Option<u64> = (frame_count as u64)
+        .checked_mul(width as u64)
+        .and_then(|pixels| pixels.checked_mul(height as u64));
+    match pixels {
+        Some(pixels) if pixels <= MAX_GIF_PIXELS => {}
         _ => {
-            web_sys::console::log_1(&format!("failed to match dot_id {:?}", &dot_id).into());
-            return false;
+            return Err(AppError::invalid_value(
+                "frame_count",
+                &format!(
+                    "times the frame size must be at most {} pixels",
+                    MAX_GIF_PIXELS
+                ),
+            )
+            .into());
         }
     };
+    let mut frames = render_frames(
+        &app.state.diagram,
+        &app.state.style,
+        &animation,
+        frame_count,
+        width,
+    )
+    .ok_or_else(frames_too_large)?;
+    return encode_gif(&mut frames, frames_per_second).ok_or_else(|| {
+        AppError::Encoding {
+            format: "gif",
+            reason: "the frames could not be written".to_string(),
+        }
+        .into()
+    });
+}
 
-    web_sys::console::log_1(&format!("bound dot",).into());
+/// Exports a single frame of an animation of the diagram as a png, for
+/// writing out numbered frame sequences. Throws an `AppError` if the
+/// frame is not one of `frame_count` or the width is out of range, as
+/// for `export_gif`.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_png_frame(
+    app: &App,
+    animation: &str,
+    frame_index: u32,
+    frame_count: u32,
+    width: u32,
+) -> Result<Vec<u8>, JsValue> {
+    let animation = get_frame_animation(animation)?;
+    check_frames(frame_count, width)?;
+    if frame_index >= frame_count {
+        return Err(AppError::invalid_value("frame_index", "must be less than frame_count").into());
+    }
+    let frame = render_frame(
+        &app.state.diagram,
+        &app.state.style,
+        &animation,
+        frame_time(&animation, frame_index, frame_count),
+        width,
+    )
+    .ok_or_else(frames_too_large)?;
+    return encode_png(&frame).ok_or_else(|| {
+        AppError::Encoding {
+            format: "png",
+            reason: "the frame could not be written".to_string(),
+        }
+        .into()
+    });
+}
+
+/// The file name of a frame in a numbered png sequence
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn frame_file_name(frame_index: u32, frame_count: u32) -> String {
+    return png_frame_file_name(frame_index, frame_count);
+}
 
-    match input_name.as_ref() {
-        "circle_radius" => {
-            web_sys::console::log_1(&format!("write circle_radius",).into());
-            default_dot.circle_radius = new_value;
+/// Exports the current diagram as a binary STL for 3d printing.
+///
+/// Dots are raised `dot_height` millimeters above the base plate, or cut
+/// that deep into it when `recessed` is set. Throws an `AppError` if the
+/// scale or dot height is not above 0, or the base is thinner than 0.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_stl(
+    app: &App,
+    recessed: bool,
+    scale: f64,
+    base_thickness: f64,
+    dot_height: f64,
+) -> Result<Vec<u8>, JsValue> {
+    for (field, value) in [("scale", scale), ("dot_height", dot_height)].iter() {
+        if !(*value > 0.0 && value.is_finite()) {
+            return Err(AppError::invalid_value(field, "must be a number above 0").into());
         }
-        "ring_radius" => {
-            web_sys::console::log_1(&format!("write ring_radius",).into());
-            default_dot.ring_radius = new_value;
+    }
+    if !(base_thickness >= 0.0 && base_thickness.is_finite()) {
+        return Err(
+            AppError::invalid_value("base_thickness", "must be a number of at least 0").into(),
+        );
+    }
+    let options = EmbossOptions {
+        mode: if recessed {
+            EmbossMode::Recessed
+        } else {
+            EmbossMode::Raised
+        },
+        scale: scale,
+        base_thickness: base_thickness,
+        dot_height: dot_height,
+        ..EmbossOptions::default()
+    };
+
+    return Ok(diagram_to_mesh(&app.state.diagram, &app.state.style, &options).to_binary_stl());
+}
+
+/// The current design packed into a url fragment, without its `#`
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_share_fragment(app: &App) -> String {
+    return get_share_fragment(&app.state);
+}
+
+/// Saves the current design as a document, as "json" or "toml".
+///
+/// Throws an `AppError` if the format is not recognised or the document
+/// could not be written.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn export_document(app: &App, format_name: &str) -> Result<String, JsValue> {
+    let format = DocumentFormat::from_name(format_name)
+        .ok_or_else(|| AppError::unknown_id("document format", format_name))?;
+    return Document::new(&app.state.style, &app.state.diagram)
+        .save(&format)
+        .map_err(|error| {
+            AppError::Encoding {
+                format: format.get_name(),
+                reason: format!("{}", error),
+            }
+            .into()
+        });
+}
+
+/// What `dispatch` tells js about an action
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum DispatchResult {
+    Applied {
+        warnings: Vec<String>,
+    },
+    Rejected {
+        /// From `AppError::get_kind`
+        kind: &'static str,
+        error: String,
+    },
+}
+
+/// Applies an `Action`, given as json, to the app. Returns a json
+/// `DispatchResult`, either `{"status": "applied", "warnings": [...]}`
+/// or `{"status": "rejected", "kind": "...", "error": "..."}`.
+#[allow(dead_code)]
+#[wasm_bindgen]
+pub fn dispatch(app: &mut App, action_json: &str) -> String {
+    let result: Result<Vec<String>, AppError> = serde_json::from_str(action_json)
+        .map_err(AppError::InvalidAction)
+        .and_then(|action: Action| apply_action(app, action));
+    let result: DispatchResult = match result {
+        Ok(warnings) => DispatchResult::Applied { warnings: warnings },
+        Err(error) => {
+            web_sys::console::log_1(&format!("{}", error).into());
+            DispatchResult::Rejected {
+                kind: error.get_kind(),
+                error: format!("{}", error),
+            }
         }
-        "ring_stroke_width" => {
-            web_sys::console::log_1(&format!("write ring_stroke_width",).into());
-            default_dot.ring_stroke_width = new_value;
+    };
+    // a plain enum of strings always serializes
+    return serde_json::to_string(&result).unwrap();
+}
+
+/// Reverts the last edit. Returns false if there was nothing to undo.
+#[allow(dead_code)]
+#[wasm_bindgen]
+pub fn undo(app: &mut App) -> bool {
+    let current: DesignSnapshot = DesignSnapshot::take(&app.state);
+    return match app.history.undo(current) {
+        Some(previous) => {
+            previous.restore(&mut app.state);
+            true
         }
-        _ => {
-            web_sys::console::log_1(&format!("unexpected input name {:?}", &input_name).into());
-            return false;
+        None => false,
+    };
+}
+
+/// Reapplies the last undone edit. Returns false if there was nothing
+/// to redo.
+#[allow(dead_code)]
+#[wasm_bindgen]
+pub fn redo(app: &mut App) -> bool {
+    let current: DesignSnapshot = DesignSnapshot::take(&app.state);
+    return match app.history.redo(current) {
+        Some(next) => {
+            next.restore(&mut app.state);
+            true
         }
+        None => false,
     };
+}
 
-    return true;
+/// Ends a continuous edit, like dragging a slider, so the next edit of
+/// the same input is undone separately
+#[allow(dead_code)]
+#[wasm_bindgen]
+pub fn finish_edit(app: &mut App) {
+    app.history.finish_edit();
 }
 
-/// Updates the rendered app
+/// Updates the rendered app.
+///
+/// Throws an `AppError` if the app isn't mounted, or if the page could
+/// not be patched. The app is rendered again from scratch in that case,
+/// so the page still matches the state.
 #[allow(dead_code)]
 #[wasm_bindgen]
-pub fn rerender_app(host_elem: Element, app: &mut App) -> bool {
+pub fn rerender_app(host_elem: Element, app: &mut App) -> Result<(), JsValue> {
     web_sys::console::log_1(&"rendering app".into());
+    let mut result: Result<(), AppError> = Ok(());
     match &mut app.mount_state {
         None => {
-            web_sys::console::log_1(&"no mount state - not rerendering".into());
-            return false;
+            return Err(AppError::NotMounted.into());
         }
         Some(mount_state) => {
             web_sys::console::log_1(&"rendering".into());
             let next_dom = ApplicationRenderer::render(&app.state);
             web_sys::console::log_1(&"diffing".into());
             let patches = virtual_dom_rs::diff(&mount_state.dom, &next_dom);
+            let diagram_markup: String = get_diagram_markup(&next_dom);
 
-            // dom patching consumes the node
+            // dom patching consumes the node. patches are numbered from
+            // the app's root element, not the host it was mounted in.
             web_sys::console::log_1(&format!("applying patches {:?}", patches).into());
-            let patch_result = virtual_dom_rs::patch(host_elem, &patches);
+            let patch_result = match host_elem.first_element_child() {
+                Some(root_elem) => virtual_dom_rs::patch(root_elem, &patches).map(|_| ()),
+                None => Err(JsValue::from_str("the app has no root element")),
+            };
 
-            match patch_result {
-                Ok(_) => {}
-                Err(e) => {
-                    web_sys::console::log_1(&format!("failure during patching {:?}", e).into())
-                }
+            if let Err(e) = patch_result {
+                host_elem.set_inner_html(&to_markup(&next_dom));
+                result = Err(AppError::Patch(format!("{:?}", e)));
+            } else if diagram_markup != mount_state.diagram_markup {
+                result = replace_diagram(&host_elem, &diagram_markup);
             }
 
             web_sys::console::log_1(&"updating mount state".into());
-            app.mount_state = Some(AppMount { dom: next_dom });
+            app.mount_state = Some(AppMount {
+                dom: next_dom,
+                diagram_markup: diagram_markup,
+            });
+            paint_dots_canvas(&app.state);
+            write_location_hash(&app.state);
         }
     };
 
-    return true;
+    return result.map_err(JsValue::from);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOSTILE_TEXT: &str = "</textarea><img src=x onerror=alert(1)>";
+
+    #[test]
+    fn shared_designs_are_rendered_as_text() {
+        let mut shared_state: FullApplicationState = get_initial_state();
+        shared_state.diagram.paths[0].text = HOSTILE_TEXT.to_string();
+        shared_state.style.save_preset(HOSTILE_TEXT);
+        let fragment: String = get_share_fragment(&shared_state);
+
+        let mut state: FullApplicationState = get_initial_state();
+        let warnings: Vec<String> =
+            restore_shared_design(&mut state, decode_share_fragment(&fragment).unwrap());
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let markup: String = to_markup(&ApplicationRenderer::render(&state));
+        assert!(!markup.contains("<img"));
+        assert!(markup.contains("&lt;/textarea&gt;&lt;img src=x onerror=alert(1)&gt;"));
+    }
+
+    #[test]
+    fn shared_designs_with_script_center_images_are_not_restored() {
+        let mut shared_state: FullApplicationState = get_initial_state();
+        shared_state.diagram.background.center_image =
+            Some("javascript:alert(1)//data:image/png;base64,".to_string());
+        let fragment: String = get_share_fragment(&shared_state);
+
+        assert!(decode_share_fragment(&fragment).is_err());
+    }
+
+    #[test]
+    fn ring_text_is_escaped_in_the_ring_input_value() {
+        let mut state: FullApplicationState = get_initial_state();
+        state.diagram.paths[0].text = "\"><img src=x onerror=alert(1)>".to_string();
+
+        let markup: String = to_markup(&ApplicationRenderer::render(&state));
+        assert!(!markup.contains("<img"));
+        assert!(markup.contains("value=\"&quot;&gt;&lt;img src=x onerror=alert(1)&gt;\""));
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod browser_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+    #[wasm_bindgen_test]
+    fn added_rings_are_svg_elements() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let host: Element = document.create_element("section").unwrap();
+        let mut app = App {
+            state: get_initial_state(),
+            history: History::new(),
+            mount_state: None,
+        };
+        mount(host.clone(), &mut app).unwrap();
+
+        apply_action(
+            &mut app,
+            Action::AddRing {
+                text: "new".to_string(),
+            },
+        )
+        .unwrap();
+        rerender_app(host.clone(), &mut app).unwrap();
+
+        // rings are the last children of the svg, after its background
+        let ring: Element = host
+            .query_selector(".dot-ring-img > g:last-child")
+            .unwrap()
+            .unwrap();
+        let dots: Element = ring.last_element_child().unwrap();
+        assert_eq!(ring.namespace_uri(), Some(SVG_NAMESPACE.to_string()));
+        assert_eq!(dots.namespace_uri(), Some(SVG_NAMESPACE.to_string()));
+    }
 }
@@ -0,0 +1,451 @@
+use crate::drawing_style::DrawingStyle;
+use crate::fig::diagram::Diagram;
+use crate::fig::shape::ShapeGeometry;
+use crate::fig::text_path::DotPlacement;
+use crate::float_utils::fmax;
+use crate::geom::{Rect, Vector3};
+use crate::stl::mesh::Mesh;
+
+pub enum EmbossMode {
+    /// Dots stand up from the top of the base plate
+    Raised,
+    /// Dots are cut down into the top of the base plate
+    Recessed,
+}
+
+pub struct EmbossOptions {
+    pub mode: EmbossMode,
+    /// Millimeters per diagram unit
+    pub scale: f64,
+    /// Thickness of the plate under the dots, in millimeters
+    pub base_thickness: f64,
+    /// Height of raised dots or depth of recessed dots, in millimeters
+    pub dot_height: f64,
+    /// Number of segments used to approximate each circle
+    pub circle_segments: usize,
+    /// Size of the sampling grid used to cut recesses, in diagram units.
+    /// Large diagrams are sampled more coarsely, to stay within
+    /// `MAX_RECESS_CELLS`.
+    pub recess_resolution: f64,
+}
+
+/// Most cells in the grid recesses are cut from. Each cell becomes at
+/// least four triangles in the mesh.
+pub const MAX_RECESS_CELLS: f64 = 262144.0;
+
+impl Default for EmbossOptions {
+    fn default() -> EmbossOptions {
+        return EmbossOptions {
+            mode: EmbossMode::Raised,
+            scale: 2.0,
+            base_thickness: 2.0,
+            dot_height: 1.0,
+            circle_segments: 32,
+            recess_resolution: 0.1,
+        };
+    }
+}
+
+/// Builds a printable mesh of the diagram on top of a base plate
+/// sized from the diagram's bounding rect.
+///
+/// Raised dots are emitted as separate solids resting on the plate,
+/// which slicers union when printing. Holes in custom glyphs are filled
+/// in when raised, since each outline is extruded on its own.
+///
+/// Recessed dots are cut from a heightfield sampled on a grid, since
+/// subtracting circles from the plate exactly would need real CSG.
+pub fn diagram_to_mesh(diagram: &Diagram, style: &DrawingStyle, options: &EmbossOptions) -> Mesh {
+    let bounds: Rect = diagram.get_bounding_rect(style);
+    let placements: Vec<DotPlacement> = diagram.get_dot_placements(style);
+
+    return match options.mode {
+        EmbossMode::Raised => raised_mesh(&bounds, &placements, options),
+        EmbossMode::Recessed => recessed_mesh(&bounds, &placements, options),
+    };
+}
+
+fn raised_mesh(bounds: &Rect, placements: &Vec<DotPlacement>, options: &EmbossOptions) -> Mesh {
+    let mut mesh = Mesh::new();
+    let plate_top = options.base_thickness;
+    let dot_top = options.base_thickness + options.dot_height;
+
+    push_box(
+        &mut mesh,
+        0.0,
+        0.0,
+        0.0,
+        bounds.width * options.scale,
+        bounds.height * options.scale,
+        plate_top,
+    );
+
+    for placement in placements.iter() {
+        // flip y so the print reads the same way as the svg from above
+        let x = (placement.x - bounds.x) * options.scale;
+        let y = (bounds.height - (placement.y - bounds.y)) * options.scale;
+        let dot = &placement.dot;
+
+        match dot.get_shape_geometry() {
+            ShapeGeometry::Circle(radius) => push_annulus(
+                &mut mesh,
+                x,
+                y,
+                0.0,
+                radius * options.scale,
+                plate_top,
+                dot_top,
+                options.circle_segments,
+            ),
+            ShapeGeometry::Polygons(polygons) => {
+                for polygon in polygons.iter() {
+                    let print_polygon: Vec<(f64, f64)> = polygon
+                        .iter()
+                        .map(|point| (x + point.x * options.scale, y - point.y * options.scale))
+                        .collect();
+                    push_prism(&mut mesh, &print_polygon, plate_top, dot_top);
+                }
+            }
+            ShapeGeometry::Empty => {}
+        }
+
+        if dot.ring_stroke_width > 0.0 {
+            let inner_radius = dot.ring_radius - dot.ring_stroke_width / 2.0;
+            let outer_radius = dot.ring_radius + dot.ring_stroke_width / 2.0;
+            push_annulus(
+                &mut mesh,
+                x,
+                y,
+                fmax(&inner_radius, &0.0) * options.scale,
+                outer_radius * options.scale,
+                plate_top,
+                dot_top,
+                options.circle_segments,
+            );
+        }
+    }
+
+    return mesh;
+}
+
+fn is_covered_by_dot(
+    placements: &Vec<DotPlacement>,
+    shapes: &Vec<ShapeGeometry>,
+    x: f64,
+    y: f64,
+) -> bool {
+    for (placement, shape) in placements.iter().zip(shapes.iter()) {
+        let dx = x - placement.x;
+        let dy = y - placement.y;
+        let distance = f64::sqrt(dx * dx + dy * dy);
+        let dot = &placement.dot;
+
+        if shape.contains(dx, dy) {
+            return true;
+        }
+        if dot.ring_stroke_width > 0.0
+            && f64::abs(distance - dot.ring_radius) <= dot.ring_stroke_width / 2.0
+        {
+            return true;
+        }
+    }
+    return false;
+}
+
+fn recessed_mesh(bounds: &Rect, placements: &Vec<DotPlacement>, options: &EmbossOptions) -> Mesh {
+    let mut mesh = Mesh::new();
+    let recess_floor = options.base_thickness;
+    let plate_top = options.base_thickness + options.dot_height;
+
+    // cells are square, so the grid is coarsened evenly in both directions
+    let resolution: f64 = fmax(
+        &options.recess_resolution,
+        &f64::sqrt(bounds.width * bounds.height / MAX_RECESS_CELLS),
+    );
+    let columns = f64::ceil(bounds.width / resolution).max(1.0) as usize;
+    let rows = f64::ceil(bounds.height / resolution).max(1.0) as usize;
+    let cell_width = bounds.width / columns as f64;
+    let cell_height = bounds.height / rows as f64;
+
+    let shapes: Vec<ShapeGeometry> = placements
+        .iter()
+        .map(|placement| placement.dot.get_shape_geometry())
+        .collect();
+
+    // heights[row][column], with row 0 at the bottom of the print
+    let mut heights: Vec<Vec<f64>> = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let mut row_heights: Vec<f64> = Vec::with_capacity(columns);
+        let sample_y = bounds.y + bounds.height - (row as f64 + 0.5) * cell_height;
+        for column in 0..columns {
+            let sample_x = bounds.x + (column as f64 + 0.5) * cell_width;
+            row_heights.push(
+                if is_covered_by_dot(placements, &shapes, sample_x, sample_y) {
+                    recess_floor
+                } else {
+                    plate_top
+                },
+            );
+        }
+        heights.push(row_heights);
+    }
+
+    let grid_x = |column: usize| column as f64 * cell_width * options.scale;
+    let grid_y = |row: usize| row as f64 * cell_height * options.scale;
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let height = heights[row][column];
+            let (x0, x1) = (grid_x(column), grid_x(column + 1));
+            let (y0, y1) = (grid_y(row), grid_y(row + 1));
+
+            // top and bottom faces
+            mesh.push_quad(
+                vertex(x0, y0, height),
+                vertex(x1, y0, height),
+                vertex(x1, y1, height),
+                vertex(x0, y1, height),
+            );
+            mesh.push_quad(
+                vertex(x0, y0, 0.0),
+                vertex(x0, y1, 0.0),
+                vertex(x1, y1, 0.0),
+                vertex(x1, y0, 0.0),
+            );
+
+            // walls between this cell and its right / upper neighbours,
+            // facing whichever of the two cells is lower
+            if column + 1 < columns {
+                let right_height = heights[row][column + 1];
+                if height > right_height {
+                    push_wall(&mut mesh, (x1, y0), (x1, y1), right_height, height);
+                } else if height < right_height {
+                    push_wall(&mut mesh, (x1, y1), (x1, y0), height, right_height);
+                }
+            }
+            if row + 1 < rows {
+                let upper_height = heights[row + 1][column];
+                if height > upper_height {
+                    push_wall(&mut mesh, (x1, y1), (x0, y1), upper_height, height);
+                } else if height < upper_height {
+                    push_wall(&mut mesh, (x0, y1), (x1, y1), height, upper_height);
+                }
+            }
+
+            // outer walls, split at the recess floor so their edges line
+            // up with the interior walls
+            let mut outer_walls: Vec<((f64, f64), (f64, f64))> = vec![];
+            if column == 0 {
+                outer_walls.push(((x0, y1), (x0, y0)));
+            }
+            if column + 1 == columns {
+                outer_walls.push(((x1, y0), (x1, y1)));
+            }
+            if row == 0 {
+                outer_walls.push(((x0, y0), (x1, y0)));
+            }
+            if row + 1 == rows {
+                outer_walls.push(((x1, y1), (x0, y1)));
+            }
+            for (from, to) in outer_walls {
+                push_wall(&mut mesh, from, to, 0.0, recess_floor);
+                if height > recess_floor {
+                    push_wall(&mut mesh, from, to, recess_floor, height);
+                }
+            }
+        }
+    }
+
+    return mesh;
+}
+
+fn vertex(x: f64, y: f64, z: f64) -> Vector3 {
+    return Vector3 { x: x, y: y, z: z };
+}
+
+/// Pushes a vertical wall between two points, facing to the right of
+/// the direction from `from` to `to` when viewed from above.
+fn push_wall(mesh: &mut Mesh, from: (f64, f64), to: (f64, f64), z0: f64, z1: f64) {
+    mesh.push_quad(
+        vertex(from.0, from.1, z0),
+        vertex(to.0, to.1, z0),
+        vertex(to.0, to.1, z1),
+        vertex(from.0, from.1, z1),
+    );
+}
+
+fn push_box(mesh: &mut Mesh, x0: f64, y0: f64, z0: f64, x1: f64, y1: f64, z1: f64) {
+    mesh.push_quad(
+        vertex(x0, y0, z0),
+        vertex(x0, y1, z0),
+        vertex(x1, y1, z0),
+        vertex(x1, y0, z0),
+    );
+    mesh.push_quad(
+        vertex(x0, y0, z1),
+        vertex(x1, y0, z1),
+        vertex(x1, y1, z1),
+        vertex(x0, y1, z1),
+    );
+    push_wall(mesh, (x0, y0), (x1, y0), z0, z1);
+    push_wall(mesh, (x1, y0), (x1, y1), z0, z1);
+    push_wall(mesh, (x1, y1), (x0, y1), z0, z1);
+    push_wall(mesh, (x0, y1), (x0, y0), z0, z1);
+}
+
+fn cross(origin: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    return (a.0 - origin.0) * (b.1 - origin.1) - (a.1 - origin.1) * (b.0 - origin.0);
+}
+
+/// Splits a simple counter-clockwise polygon into triangles by ear
+/// clipping, returning indices into `polygon`
+fn triangulate_polygon(polygon: &Vec<(f64, f64)>) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles: Vec<[usize; 3]> = vec![];
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&index| {
+            let prev = remaining[(index + count - 1) % count];
+            let current = remaining[index];
+            let next = remaining[(index + 1) % count];
+            let (a, b, c) = (polygon[prev], polygon[current], polygon[next]);
+            if cross(a, b, c) <= 0.0 {
+                return false;
+            }
+            return !remaining.iter().any(|&other| {
+                other != prev
+                    && other != current
+                    && other != next
+                    && cross(a, b, polygon[other]) >= 0.0
+                    && cross(b, c, polygon[other]) >= 0.0
+                    && cross(c, a, polygon[other]) >= 0.0
+            });
+        });
+
+        match ear {
+            Some(index) => {
+                triangles.push([
+                    remaining[(index + count - 1) % count],
+                    remaining[index],
+                    remaining[(index + 1) % count],
+                ]);
+                remaining.remove(index);
+            }
+            // not a simple polygon, so give up on what's left of it
+            None => return triangles,
+        }
+    }
+
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    return triangles;
+}
+
+/// Pushes a polygon extruded vertically between z0 and z1
+fn push_prism(mesh: &mut Mesh, polygon: &Vec<(f64, f64)>, z0: f64, z1: f64) {
+    let mut outline: Vec<(f64, f64)> = Vec::with_capacity(polygon.len());
+    for point in polygon.iter() {
+        if outline.last() != Some(point) {
+            outline.push(*point);
+        }
+    }
+    while outline.len() > 1 && outline.first() == outline.last() {
+        outline.pop();
+    }
+    if outline.len() < 3 {
+        return;
+    }
+
+    let signed_area: f64 = (0..outline.len())
+        .map(|index| {
+            cross(
+                (0.0, 0.0),
+                outline[index],
+                outline[(index + 1) % outline.len()],
+            )
+        })
+        .sum();
+    if signed_area < 0.0 {
+        outline.reverse();
+    }
+
+    for [a, b, c] in triangulate_polygon(&outline) {
+        let (a, b, c) = (outline[a], outline[b], outline[c]);
+        mesh.push_triangle(
+            vertex(a.0, a.1, z1),
+            vertex(b.0, b.1, z1),
+            vertex(c.0, c.1, z1),
+        );
+        mesh.push_triangle(
+            vertex(a.0, a.1, z0),
+            vertex(c.0, c.1, z0),
+            vertex(b.0, b.1, z0),
+        );
+    }
+    for index in 0..outline.len() {
+        push_wall(
+            mesh,
+            outline[index],
+            outline[(index + 1) % outline.len()],
+            z0,
+            z1,
+        );
+    }
+}
+
+/// Pushes an extruded annulus centered on (x, y).
+///
+/// An inner radius of 0 produces a solid cylinder.
+fn push_annulus(
+    mesh: &mut Mesh,
+    x: f64,
+    y: f64,
+    inner_radius: f64,
+    outer_radius: f64,
+    z0: f64,
+    z1: f64,
+    segments: usize,
+) {
+    let point = |radius: f64, segment: usize| {
+        let angle = segment as f64 / segments as f64 * std::f64::consts::PI * 2.0;
+        return (x + radius * f64::cos(angle), y + radius * f64::sin(angle));
+    };
+
+    for segment in 0..segments {
+        let outer_start = point(outer_radius, segment);
+        let outer_end = point(outer_radius, segment + 1);
+        push_wall(mesh, outer_start, outer_end, z0, z1);
+
+        if inner_radius > 0.0 {
+            let inner_start = point(inner_radius, segment);
+            let inner_end = point(inner_radius, segment + 1);
+            push_wall(mesh, inner_end, inner_start, z0, z1);
+
+            mesh.push_quad(
+                vertex(inner_start.0, inner_start.1, z1),
+                vertex(outer_start.0, outer_start.1, z1),
+                vertex(outer_end.0, outer_end.1, z1),
+                vertex(inner_end.0, inner_end.1, z1),
+            );
+            mesh.push_quad(
+                vertex(inner_start.0, inner_start.1, z0),
+                vertex(inner_end.0, inner_end.1, z0),
+                vertex(outer_end.0, outer_end.1, z0),
+                vertex(outer_start.0, outer_start.1, z0),
+            );
+        } else {
+            mesh.push_triangle(
+                vertex(x, y, z1),
+                vertex(outer_start.0, outer_start.1, z1),
+                vertex(outer_end.0, outer_end.1, z1),
+            );
+            mesh.push_triangle(
+                vertex(x, y, z0),
+                vertex(outer_end.0, outer_end.1, z0),
+                vertex(outer_start.0, outer_start.1, z0),
+            );
+        }
+    }
+}
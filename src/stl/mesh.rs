@@ -0,0 +1,67 @@
+use crate::geom::Vector3;
+
+pub struct Triangle {
+    pub vertices: [Vector3; 3],
+}
+
+impl Triangle {
+    /// Face normal, assuming counter-clockwise winding when viewed
+    /// from outside the solid
+    pub fn normal(&self) -> Vector3 {
+        let [a, b, c] = &self.vertices;
+        return b.sub(a).cross(&c.sub(a)).normalized();
+    }
+}
+
+pub struct Mesh {
+    pub triangles: Vec<Triangle>,
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        return Mesh { triangles: vec![] };
+    }
+
+    pub fn push_triangle(&mut self, a: Vector3, b: Vector3, c: Vector3) {
+        self.triangles.push(Triangle {
+            vertices: [a, b, c],
+        });
+    }
+
+    /// Pushes a planar quad as two triangles.
+    ///
+    /// Vertices should be given counter-clockwise as seen from
+    /// outside the solid.
+    pub fn push_quad(&mut self, a: Vector3, b: Vector3, c: Vector3, d: Vector3) {
+        self.push_triangle(a, b, c);
+        self.push_triangle(a, c, d);
+    }
+
+    /// Serializes the mesh in the binary STL format
+    pub fn to_binary_stl(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(84 + self.triangles.len() * 50);
+
+        let mut header = [0u8; 80];
+        let header_text = b"radial-dots";
+        header[..header_text.len()].copy_from_slice(header_text);
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&(self.triangles.len() as u32).to_le_bytes());
+
+        for triangle in self.triangles.iter() {
+            push_vector(&mut bytes, &triangle.normal());
+            for vertex in triangle.vertices.iter() {
+                push_vector(&mut bytes, vertex);
+            }
+            // attribute byte count, unused
+            bytes.extend_from_slice(&0u16.to_le_bytes());
+        }
+
+        return bytes;
+    }
+}
+
+fn push_vector(bytes: &mut Vec<u8>, vector: &Vector3) {
+    bytes.extend_from_slice(&(vector.x as f32).to_bits().to_le_bytes());
+    bytes.extend_from_slice(&(vector.y as f32).to_bits().to_le_bytes());
+    bytes.extend_from_slice(&(vector.z as f32).to_bits().to_le_bytes());
+}
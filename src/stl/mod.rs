@@ -0,0 +1,2 @@
+pub mod emboss;
+pub mod mesh;
@@ -0,0 +1,261 @@
+use crate::drawing_style::DrawingStyle;
+use crate::fig::diagram::Diagram;
+use crate::fig::text_path::DotPlacement;
+use crate::float_utils::{fmax, fmin};
+use crate::geom::{Rect, Vector2};
+use crate::svg::util::*;
+
+pub enum AnimationKind {
+    /// Dots fade in one at a time, in bit order across every ring
+    AppearInOrder,
+    /// Each ring spins around the center of the diagram
+    RotateRings,
+    /// A highlight travels around each ring, dimming the other dots
+    HighlightSweep,
+}
+
+impl AnimationKind {
+    pub fn from_name(name: &str) -> Option<AnimationKind> {
+        return match name {
+            "appear" => Some(AnimationKind::AppearInOrder),
+            "rotate" => Some(AnimationKind::RotateRings),
+            "sweep" => Some(AnimationKind::HighlightSweep),
+            _ => None,
+        };
+    }
+}
+
+pub enum AnimationEasing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Control points of a cubic bezier, as in css `cubic-bezier()`
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl AnimationEasing {
+    /// Parses a named easing, or `cubic-bezier(x1, y1, x2, y2)` as in
+    /// css. SMIL only allows control points within 0 to 1, so any other
+    /// point is None.
+    pub fn from_name(name: &str) -> Option<AnimationEasing> {
+        let name: String = name.trim().to_ascii_lowercase();
+        if let Some(open) = name.find('(') {
+            if &name[..open] != "cubic-bezier" || !name.ends_with(')') {
+                return None;
+            }
+            return parse_cubic_bezier(&name[open + 1..name.len() - 1]);
+        }
+        return match name.as_str() {
+            "linear" => Some(AnimationEasing::Linear),
+            "ease-in" => Some(AnimationEasing::EaseIn),
+            "ease-out" => Some(AnimationEasing::EaseOut),
+            "ease-in-out" => Some(AnimationEasing::EaseInOut),
+            _ => None,
+        };
+    }
+
+    /// The SMIL keySpline equivalent to this easing
+    fn key_spline(&self) -> String {
+        let (x1, y1, x2, y2) = match self {
+            AnimationEasing::Linear => (0.0, 0.0, 1.0, 1.0),
+            AnimationEasing::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            AnimationEasing::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            AnimationEasing::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+            AnimationEasing::CubicBezier(x1, y1, x2, y2) => (*x1, *y1, *x2, *y2),
+        };
+        return format!("{} {} {} {}", x1, y1, x2, y2);
+    }
+}
+
+/// The four comma separated control points of a `cubic-bezier()`
+fn parse_cubic_bezier(arguments: &str) -> Option<AnimationEasing> {
+    let mut points: Vec<f64> = vec![];
+    for argument in arguments.split(',') {
+        let point: f64 = argument.trim().parse().ok()?;
+        if !(point >= 0.0 && point <= 1.0) {
+            return None;
+        }
+        points.push(point);
+    }
+    return match points.as_slice() {
+        [x1, y1, x2, y2] => Some(AnimationEasing::CubicBezier(*x1, *y1, *x2, *y2)),
+        _ => None,
+    };
+}
+
+pub struct AnimationOptions {
+    pub kind: AnimationKind,
+    /// Length of one loop of the animation
+    pub duration_seconds: f64,
+    pub easing: AnimationEasing,
+}
+
+/// Fraction of the loop spent fading dots in for `AppearInOrder`.
+/// The rest of the loop holds the finished diagram.
+const APPEAR_FRACTION: f64 = 0.8;
+/// Opacity of dots outside the highlight for `HighlightSweep`
+const SWEEP_DIM_OPACITY: f64 = 0.35;
+
+/// Builds a looping SMIL `<animate>` element over `values`.
+///
+/// `eased_segment` is the index of the only key time interval that uses
+/// the easing; every other interval is linear.
+fn animate_element(
+    attribute_name: &str,
+    values: &[f64],
+    key_times: &[f64],
+    eased_segment: usize,
+    options: &AnimationOptions,
+) -> String {
+    let key_splines: Vec<String> = (0..key_times.len() - 1)
+        .map(|segment| {
+            if segment == eased_segment {
+                options.easing.key_spline()
+            } else {
+                AnimationEasing::Linear.key_spline()
+            }
+        })
+        .collect();
+
+    return format!(
+        concat!(
+            "<animate attributeName=\"{attribute_name}\" ",
+            "values=\"{values}\" ",
+            "keyTimes=\"{key_times}\" ",
+            "calcMode=\"spline\" ",
+            "keySplines=\"{key_splines}\" ",
+            "dur=\"{duration}s\" ",
+            "repeatCount=\"indefinite\" />",
+        ),
+        attribute_name = attribute_name,
+        values = join_numbers(values),
+        key_times = join_numbers(key_times),
+        key_splines = key_splines.join(";"),
+        duration = options.duration_seconds,
+    );
+}
+
+fn join_numbers(numbers: &[f64]) -> String {
+    let parts: Vec<String> = numbers.iter().map(|number| number.to_string()).collect();
+    return parts.join(";");
+}
+
+fn ring_rotation(options: &AnimationOptions) -> String {
+    return format!(
+        concat!(
+            "<animateTransform attributeName=\"transform\" type=\"rotate\" ",
+            "from=\"0 0 0\" to=\"360 0 0\" ",
+            "calcMode=\"spline\" keyTimes=\"0;1\" keySplines=\"{key_spline}\" ",
+            "dur=\"{duration}s\" repeatCount=\"indefinite\" />",
+        ),
+        key_spline = options.easing.key_spline(),
+        duration = options.duration_seconds,
+    );
+}
+
+/// The animation for a single dot, given its position in the whole
+/// diagram and within its own ring
+fn dot_animation(
+    options: &AnimationOptions,
+    diagram_index: usize,
+    diagram_dot_count: usize,
+    ring_dot_index: usize,
+    ring_dot_count: usize,
+) -> String {
+    match options.kind {
+        AnimationKind::AppearInOrder => {
+            let start = diagram_index as f64 / diagram_dot_count as f64 * APPEAR_FRACTION;
+            let end = (diagram_index + 1) as f64 / diagram_dot_count as f64 * APPEAR_FRACTION;
+            return animate_element(
+                "opacity",
+                &[0.0, 0.0, 1.0, 1.0],
+                &[0.0, start, end, 1.0],
+                1,
+                options,
+            );
+        }
+        AnimationKind::HighlightSweep => {
+            // matches the arc position used by TextPath's layout
+            let peak = (ring_dot_index + 1) as f64 / ring_dot_count as f64;
+            let half_width = 1.0 / ring_dot_count as f64;
+            let rise = fmax(&(peak - half_width), &0.0);
+            let fall = fmin(&(peak + half_width), &1.0);
+            return animate_element(
+                "opacity",
+                &[
+                    SWEEP_DIM_OPACITY,
+                    SWEEP_DIM_OPACITY,
+                    1.0,
+                    SWEEP_DIM_OPACITY,
+                    SWEEP_DIM_OPACITY,
+                ],
+                &[0.0, rise, peak, fall, 1.0],
+                1,
+                options,
+            );
+        }
+        AnimationKind::RotateRings => return "".to_string(),
+    }
+}
+
+/// Builds a standalone svg of the diagram with SMIL animations
+pub fn animated_diagram_svg(
+    diagram: &Diagram,
+    style: &DrawingStyle,
+    options: &AnimationOptions,
+) -> String {
+    let diagram_bounds: Rect = diagram.get_bounding_rect(style);
+    let diagram_center: Vector2 = diagram_bounds.center();
+    let diagram_dot_count: usize = diagram.get_dot_placements(style).len();
+
+    let mut svg_parts: Vec<String> = Vec::with_capacity(diagram.paths.len() + 1);
+    svg_parts.push(diagram.get_background_svg(style));
+
+    let mut diagram_index: usize = 0;
+    for (ring_index, path) in diagram.paths.iter().enumerate() {
+        let placements: Vec<DotPlacement> =
+            path.get_dot_placements(style, ring_index, diagram.paths.len());
+        let mut dots: Vec<String> = Vec::with_capacity(placements.len());
+        for placement in placements.iter() {
+            let animation: String = dot_animation(
+                options,
+                diagram_index,
+                diagram_dot_count,
+                placement.bit_index,
+                placements.len(),
+            );
+            dots.push(translate_svg(
+                &format!(
+                    "{}{}",
+                    placement.dot.as_colored_svg(&placement.color),
+                    animation
+                ),
+                placement.x,
+                placement.y,
+            ));
+            diagram_index += 1;
+        }
+
+        let ring_animation: String = match options.kind {
+            AnimationKind::RotateRings => ring_rotation(options),
+            _ => "".to_string(),
+        };
+        svg_parts.push(translate_svg(
+            &labelled_group_svg(
+                &diagram.get_ring_label(ring_index),
+                &format!("{}{}", dots.join(""), ring_animation),
+            ),
+            diagram_center.x,
+            diagram_center.y,
+        ));
+    }
+
+    return svg_document(
+        &diagram_bounds,
+        diagram.print_scale,
+        &diagram.get_accessible_title(),
+        &diagram.get_accessible_description(),
+        &svg_parts.join(""),
+    );
+}
@@ -0,0 +1,90 @@
+use crate::document::{Document, DocumentError, DocumentFormat, LoadedDocument};
+use crate::svg::util::escape_xml;
+
+/// Namespace of the element the document is embedded in, so other svg
+/// tools know to leave it alone
+const DOCUMENT_NAMESPACE: &str = "urn:radial-dots:document";
+const DOCUMENT_ELEMENT: &str = "radial-dots:document";
+
+/// A `<metadata>` element holding the design as a json document
+pub fn document_metadata_svg(document: &Document) -> Result<String, DocumentError> {
+    return Ok(format!(
+        "<metadata><{element} xmlns:radial-dots='{namespace}'>{document}</{element}></metadata>",
+        element = DOCUMENT_ELEMENT,
+        namespace = DOCUMENT_NAMESPACE,
+        document = escape_xml(&document.save(&DocumentFormat::Json)?)
+    ));
+}
+
+/// Reverses `escape_xml`, along with numeric character references that
+/// editors may have rewritten it into
+fn unescape_xml(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest: &str = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end: usize = start + rest[start..].find(';')?;
+        let entity: &str = &rest[start + 1..end];
+        let c: char = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ if entity.starts_with("#x") => {
+                std::char::from_u32(u32::from_str_radix(&entity[2..], 16).ok()?)?
+            }
+            _ if entity.starts_with('#') => std::char::from_u32(entity[1..].parse().ok()?)?,
+            _ => return None,
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    return Some(unescaped);
+}
+
+/// The json document embedded in an svg exported with
+/// `document_metadata_svg`, if there is one
+pub fn find_embedded_document(svg: &str) -> Option<String> {
+    let open_tag: String = format!("<{}", DOCUMENT_ELEMENT);
+    let close_tag: String = format!("</{}>", DOCUMENT_ELEMENT);
+    let element_start: usize = svg.find(&open_tag)?;
+    let content_start: usize = element_start + svg[element_start..].find('>')? + 1;
+    let content_end: usize = content_start + svg[content_start..].find(&close_tag)?;
+
+    let content: &str = svg[content_start..content_end].trim();
+    // some editors wrap text content in CDATA when they save
+    if content.starts_with("<![CDATA[") && content.ends_with("]]>") {
+        return Some(content[9..content.len() - 3].to_string());
+    }
+    return unescape_xml(content);
+}
+
+/// Reads the ring text back out of the accessible labels of an svg
+/// without an embedded document. Styles can't be recovered this way, and
+/// designs that hide their text have nothing to read.
+pub fn find_ring_labels(svg: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for label in svg.split("aria-label=\"").skip(1) {
+        let label: &str = match label.find('"') {
+            Some(end) => &label[..end],
+            None => continue,
+        };
+        let label: String = match unescape_xml(label) {
+            Some(label) => label,
+            None => continue,
+        };
+        let prefix: String = format!("Ring {}: ", lines.len() + 1);
+        if label.starts_with(&prefix) {
+            lines.push(label[prefix.len()..].to_string());
+        }
+    }
+    return lines;
+}
+
+/// Loads the design embedded in an svg export, if it has one
+pub fn load_embedded_document(svg: &str) -> Option<Result<LoadedDocument, DocumentError>> {
+    let source: String = find_embedded_document(svg)?;
+    return Some(Document::load(&source, &DocumentFormat::Json));
+}
@@ -0,0 +1,176 @@
+use crate::drawing_style::DrawingStyle;
+use crate::fig::diagram::Diagram;
+use crate::fig::text_path::{DotPlacement, TextPath};
+use crate::geom::{Rect, Vector2};
+use crate::svg::util::*;
+use crate::utf_to_binary::BITS_PER_CHAR;
+
+/// Every distinct character of the diagram's text, in order of first
+/// appearance. A character's position here names its css class, so the
+/// classes don't give away text the design hides.
+fn get_distinct_characters(diagram: &Diagram) -> Vec<char> {
+    let mut characters: Vec<char> = vec![];
+    for character in diagram.paths.iter().flat_map(|path| path.text.chars()) {
+        if !characters.contains(&character) {
+            characters.push(character);
+        }
+    }
+    return characters;
+}
+
+/// Hovering a dot dims every dot of other characters, so each occurrence
+/// of the hovered character stands out, in any ring.
+fn interactive_stylesheet(character_count: usize) -> String {
+    let highlight_rules: Vec<String> = (0..character_count)
+        .map(|class_index| {
+            format!(
+                "svg:has(.character-{0}:hover) .character:not(.character-{0}) {{ opacity: 0.25; }}",
+                class_index
+            )
+        })
+        .collect();
+    return format!(
+        concat!(
+            "<style>",
+            ".dot {{ cursor: help; }}",
+            ".character {{ transition: opacity 0.2s; }}",
+            "{}",
+            "</style>",
+        ),
+        highlight_rules.join("")
+    );
+}
+
+/// The group of dots encoding one occurrence of a character
+fn character_group_svg(class_index: usize, dots: &[String]) -> String {
+    return format!(
+        "<g class=\"character character-{}\">{}</g>",
+        class_index,
+        dots.join("")
+    );
+}
+
+/// Tooltip text for a single dot. The character itself is left out when
+/// the design hides its text.
+fn dot_title(path: &TextPath, placement: &DotPlacement, expose_text: bool) -> String {
+    let bit: String = format!(
+        "bit {}: {}",
+        placement.bit_index % BITS_PER_CHAR,
+        if placement.value { 1 } else { 0 }
+    );
+    if !expose_text {
+        return format!("Character {}, {}", placement.char_index + 1, bit);
+    }
+
+    let character: String = match path.text.chars().nth(placement.char_index) {
+        Some(character) => character.to_string(),
+        None => "".to_string(),
+    };
+    return format!(
+        "'{}' (character {}), {}",
+        character,
+        placement.char_index + 1,
+        bit
+    );
+}
+
+/// Builds the svg for a single ring, with the dots of each character
+/// grouped together and classed by the character, so every occurrence of
+/// it can be highlighted as one
+fn interactive_path_svg(
+    path: &TextPath,
+    style: &DrawingStyle,
+    ring_index: usize,
+    ring_count: usize,
+    expose_text: bool,
+    distinct_characters: &[char],
+) -> String {
+    let class_indices: Vec<usize> = path
+        .text
+        .chars()
+        .map(|character| {
+            distinct_characters
+                .iter()
+                .position(|distinct| *distinct == character)
+                .unwrap_or(0)
+        })
+        .collect();
+    let get_class_index = |char_index: usize| -> usize {
+        return class_indices.get(char_index).cloned().unwrap_or(0);
+    };
+
+    let mut characters: Vec<String> = vec![];
+    let mut character_dots: Vec<String> = vec![];
+    let mut current_char_index: usize = 0;
+
+    for placement in path
+        .get_dot_placements(style, ring_index, ring_count)
+        .iter()
+    {
+        if placement.char_index != current_char_index && !character_dots.is_empty() {
+            characters.push(character_group_svg(
+                get_class_index(current_char_index),
+                &character_dots,
+            ));
+            character_dots.clear();
+        }
+        current_char_index = placement.char_index;
+
+        character_dots.push(format!(
+            "<g class=\"dot\" transform=\"{}\"><title>{}</title>{}</g>",
+            translate_transform(placement.x, placement.y),
+            escape_xml(&dot_title(path, placement, expose_text)),
+            placement.dot.as_colored_svg(&placement.color)
+        ));
+    }
+    if !character_dots.is_empty() {
+        characters.push(character_group_svg(
+            get_class_index(current_char_index),
+            &character_dots,
+        ));
+    }
+
+    return characters.join("");
+}
+
+/// Builds a standalone svg of the diagram where every dot has a tooltip
+/// describing the bit it encodes, and hovering a dot highlights the dots
+/// of the same character everywhere in the diagram.
+pub fn interactive_diagram_svg(diagram: &Diagram, style: &DrawingStyle) -> String {
+    let diagram_bounds: Rect = diagram.get_bounding_rect(style);
+    let diagram_center: Vector2 = diagram_bounds.center();
+    let distinct_characters: Vec<char> = get_distinct_characters(diagram);
+
+    let mut svg_parts: Vec<String> = Vec::with_capacity(diagram.paths.len() + 2);
+    svg_parts.push(interactive_stylesheet(distinct_characters.len()));
+    svg_parts.push(diagram.get_background_svg(style));
+
+    for (ring_index, path) in diagram.paths.iter().enumerate() {
+        svg_parts.push(translate_svg(
+            &format!(
+                "<g class=\"ring\">{}</g>",
+                labelled_group_svg(
+                    &diagram.get_ring_label(ring_index),
+                    &interactive_path_svg(
+                        path,
+                        style,
+                        ring_index,
+                        diagram.paths.len(),
+                        diagram.expose_text,
+                        &distinct_characters,
+                    )
+                )
+            ),
+            diagram_center.x,
+            diagram_center.y,
+        ));
+    }
+
+    return svg_document(
+        &diagram_bounds,
+        diagram.print_scale,
+        &diagram.get_accessible_title(),
+        &diagram.get_accessible_description(),
+        &svg_parts.join(""),
+    );
+}
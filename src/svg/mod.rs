@@ -1,2 +1,7 @@
+pub mod animation;
+pub mod embedded_document;
+pub mod interactive;
+pub mod path_data;
 pub mod svg_drawable;
 pub mod util;
+pub mod virtual_svg_drawable;
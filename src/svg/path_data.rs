@@ -0,0 +1,332 @@
+use crate::geom::Vector2;
+
+/// Number of line segments each curve or arc is flattened into
+const CURVE_SEGMENTS: usize = 16;
+
+enum Token {
+    Command(char),
+    Number(f64),
+}
+
+fn tokenize(path_data: &str) -> Option<Vec<Token>> {
+    let bytes = path_data.as_bytes();
+    let mut tokens: Vec<Token> = vec![];
+    let mut index: usize = 0;
+
+    while index < bytes.len() {
+        let c = bytes[index] as char;
+        if c.is_ascii_whitespace() || c == ',' {
+            index += 1;
+            continue;
+        }
+        if c.is_ascii_alphabetic() {
+            tokens.push(Token::Command(c));
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        if c == '+' || c == '-' {
+            index += 1;
+        }
+        let mut seen_digit = false;
+        let mut seen_point = false;
+        while index < bytes.len() {
+            let c = bytes[index] as char;
+            if c.is_ascii_digit() {
+                seen_digit = true;
+            } else if c == '.' && !seen_point {
+                seen_point = true;
+            } else {
+                break;
+            }
+            index += 1;
+        }
+        if !seen_digit {
+            return None;
+        }
+
+        // exponent, only consumed if digits follow it
+        if index < bytes.len() && (bytes[index] == b'e' || bytes[index] == b'E') {
+            let mut exponent_end = index + 1;
+            if exponent_end < bytes.len()
+                && (bytes[exponent_end] == b'+' || bytes[exponent_end] == b'-')
+            {
+                exponent_end += 1;
+            }
+            if exponent_end < bytes.len() && (bytes[exponent_end] as char).is_ascii_digit() {
+                index = exponent_end;
+                while index < bytes.len() && (bytes[index] as char).is_ascii_digit() {
+                    index += 1;
+                }
+            }
+        }
+
+        tokens.push(Token::Number(path_data[start..index].parse().ok()?));
+    }
+
+    return Some(tokens);
+}
+
+fn point(x: f64, y: f64) -> Vector2 {
+    return Vector2 { x: x, y: y };
+}
+
+fn angle_between(u: &Vector2, v: &Vector2) -> f64 {
+    return f64::atan2(u.x * v.y - u.y * v.x, u.x * v.x + u.y * v.y);
+}
+
+/// Flattens an elliptical arc, following the endpoint to center
+/// conversion in the svg spec (appendix F.6.5)
+fn flatten_arc(
+    from: &Vector2,
+    radius_x: f64,
+    radius_y: f64,
+    rotation_degrees: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: &Vector2,
+) -> Vec<Vector2> {
+    if from.x == to.x && from.y == to.y {
+        return vec![];
+    }
+    if radius_x == 0.0 || radius_y == 0.0 {
+        return vec![point(to.x, to.y)];
+    }
+
+    let mut radius_x = f64::abs(radius_x);
+    let mut radius_y = f64::abs(radius_y);
+    let rotation = rotation_degrees.to_radians();
+    let (sin_rotation, cos_rotation) = (f64::sin(rotation), f64::cos(rotation));
+
+    let half_dx = (from.x - to.x) / 2.0;
+    let half_dy = (from.y - to.y) / 2.0;
+    let x1 = cos_rotation * half_dx + sin_rotation * half_dy;
+    let y1 = -sin_rotation * half_dx + cos_rotation * half_dy;
+
+    // scale up radii that are too small to reach the end point
+    let lambda = (x1 * x1) / (radius_x * radius_x) + (y1 * y1) / (radius_y * radius_y);
+    if lambda > 1.0 {
+        radius_x *= f64::sqrt(lambda);
+        radius_y *= f64::sqrt(lambda);
+    }
+
+    let numerator = radius_x * radius_x * radius_y * radius_y
+        - radius_x * radius_x * y1 * y1
+        - radius_y * radius_y * x1 * x1;
+    let denominator = radius_x * radius_x * y1 * y1 + radius_y * radius_y * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * f64::sqrt(f64::max(numerator / denominator, 0.0));
+    let center_x1 = coefficient * radius_x * y1 / radius_y;
+    let center_y1 = -coefficient * radius_y * x1 / radius_x;
+
+    let center_x = cos_rotation * center_x1 - sin_rotation * center_y1 + (from.x + to.x) / 2.0;
+    let center_y = sin_rotation * center_x1 + cos_rotation * center_y1 + (from.y + to.y) / 2.0;
+
+    let start_vector = point((x1 - center_x1) / radius_x, (y1 - center_y1) / radius_y);
+    let end_vector = point((-x1 - center_x1) / radius_x, (-y1 - center_y1) / radius_y);
+    let start_angle = angle_between(&point(1.0, 0.0), &start_vector);
+    let mut sweep_angle = angle_between(&start_vector, &end_vector);
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= std::f64::consts::PI * 2.0;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += std::f64::consts::PI * 2.0;
+    }
+
+    return (1..=CURVE_SEGMENTS)
+        .map(|segment| {
+            let angle = start_angle + sweep_angle * segment as f64 / CURVE_SEGMENTS as f64;
+            let (sin_angle, cos_angle) = (f64::sin(angle), f64::cos(angle));
+            return point(
+                center_x + radius_x * cos_angle * cos_rotation
+                    - radius_y * sin_angle * sin_rotation,
+                center_y
+                    + radius_x * cos_angle * sin_rotation
+                    + radius_y * sin_angle * cos_rotation,
+            );
+        })
+        .collect();
+}
+
+fn flatten_cubic(p0: &Vector2, p1: &Vector2, p2: &Vector2, p3: &Vector2) -> Vec<Vector2> {
+    return (1..=CURVE_SEGMENTS)
+        .map(|segment| {
+            let t = segment as f64 / CURVE_SEGMENTS as f64;
+            let u = 1.0 - t;
+            return point(
+                u * u * u * p0.x
+                    + 3.0 * u * u * t * p1.x
+                    + 3.0 * u * t * t * p2.x
+                    + t * t * t * p3.x,
+                u * u * u * p0.y
+                    + 3.0 * u * u * t * p1.y
+                    + 3.0 * u * t * t * p2.y
+                    + t * t * t * p3.y,
+            );
+        })
+        .collect();
+}
+
+fn flatten_quadratic(p0: &Vector2, p1: &Vector2, p2: &Vector2) -> Vec<Vector2> {
+    return (1..=CURVE_SEGMENTS)
+        .map(|segment| {
+            let t = segment as f64 / CURVE_SEGMENTS as f64;
+            let u = 1.0 - t;
+            return point(
+                u * u * p0.x + 2.0 * u * t * p1.x + t * t * p2.x,
+                u * u * p0.y + 2.0 * u * t * p1.y + t * t * p2.y,
+            );
+        })
+        .collect();
+}
+
+/// Parses svg path data (the `d` attribute of a `<path>`) into
+/// polylines, one per subpath, with curves and arcs flattened into
+/// line segments.
+///
+/// Returns None if the path data is malformed.
+pub fn flatten_path_data(path_data: &str) -> Option<Vec<Vec<Vector2>>> {
+    let tokens: Vec<Token> = tokenize(path_data)?;
+    let mut polylines: Vec<Vec<Vector2>> = vec![];
+    let mut current: Vec<Vector2> = vec![];
+    let mut position: Vector2 = point(0.0, 0.0);
+    let mut subpath_start: Vector2 = point(0.0, 0.0);
+    // control point of the previous curve, for reflecting in S and T
+    let mut previous_control: Option<(char, Vector2)> = None;
+    let mut command: Option<char> = None;
+    let mut index: usize = 0;
+
+    while index < tokens.len() {
+        if let Token::Command(c) = tokens[index] {
+            command = Some(c);
+            index += 1;
+        }
+        let c: char = command?;
+        let relative: bool = c.is_ascii_lowercase();
+        let upper: char = c.to_ascii_uppercase();
+
+        if upper == 'Z' {
+            if current.len() > 1 {
+                polylines.push(current);
+            }
+            current = vec![];
+            position = point(subpath_start.x, subpath_start.y);
+            previous_control = None;
+            // Z takes no arguments, so numbers can't follow it
+            if let Some(Token::Number(_)) = tokens.get(index) {
+                return None;
+            }
+            continue;
+        }
+
+        let argument_count: usize = match upper {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => 7,
+            _ => return None,
+        };
+        let mut arguments: Vec<f64> = Vec::with_capacity(argument_count);
+        for offset in 0..argument_count {
+            match tokens.get(index + offset) {
+                Some(Token::Number(value)) => arguments.push(*value),
+                _ => return None,
+            }
+        }
+        index += argument_count;
+
+        let (origin_x, origin_y) = if relative {
+            (position.x, position.y)
+        } else {
+            (0.0, 0.0)
+        };
+        let absolute = |argument: usize| {
+            point(
+                arguments[argument] + origin_x,
+                arguments[argument + 1] + origin_y,
+            )
+        };
+        let reflected_control = |kinds: &[char]| match &previous_control {
+            Some((kind, control)) if kinds.contains(kind) => {
+                point(2.0 * position.x - control.x, 2.0 * position.y - control.y)
+            }
+            _ => point(position.x, position.y),
+        };
+
+        if upper == 'M' {
+            if current.len() > 1 {
+                polylines.push(current);
+            }
+            position = absolute(0);
+            subpath_start = point(position.x, position.y);
+            current = vec![point(position.x, position.y)];
+            previous_control = None;
+            // further coordinate pairs after a move are implicit lines
+            command = Some(if relative { 'l' } else { 'L' });
+            continue;
+        }
+
+        if current.is_empty() {
+            current.push(point(position.x, position.y));
+        }
+
+        let mut next_control: Option<(char, Vector2)> = None;
+        let end: Vector2 = match upper {
+            'L' => absolute(0),
+            'H' => point(arguments[0] + origin_x, position.y),
+            'V' => point(position.x, arguments[0] + origin_y),
+            'C' => {
+                let (control_1, control_2, end) = (absolute(0), absolute(2), absolute(4));
+                current.extend(flatten_cubic(&position, &control_1, &control_2, &end));
+                next_control = Some(('C', control_2));
+                end
+            }
+            'S' => {
+                let control_1 = reflected_control(&['C']);
+                let (control_2, end) = (absolute(0), absolute(2));
+                current.extend(flatten_cubic(&position, &control_1, &control_2, &end));
+                next_control = Some(('C', control_2));
+                end
+            }
+            'Q' => {
+                let (control, end) = (absolute(0), absolute(2));
+                current.extend(flatten_quadratic(&position, &control, &end));
+                next_control = Some(('Q', control));
+                end
+            }
+            'T' => {
+                let control = reflected_control(&['Q']);
+                let end = absolute(0);
+                current.extend(flatten_quadratic(&position, &control, &end));
+                next_control = Some(('Q', control));
+                end
+            }
+            'A' => {
+                let end = absolute(5);
+                current.extend(flatten_arc(
+                    &position,
+                    arguments[0],
+                    arguments[1],
+                    arguments[2],
+                    arguments[3] != 0.0,
+                    arguments[4] != 0.0,
+                    &end,
+                ));
+                end
+            }
+            _ => return None,
+        };
+
+        if upper == 'L' || upper == 'H' || upper == 'V' {
+            current.push(point(end.x, end.y));
+        }
+        position = end;
+        previous_control = next_control;
+    }
+
+    if current.len() > 1 {
+        polylines.push(current);
+    }
+    return Some(polylines);
+}
@@ -1,15 +1,20 @@
-use crate::geom::Rect;
+use crate::color::Color;
+use crate::fig::shape::ShapeGeometry;
+use crate::geom::{Rect, Vector2};
+
+pub fn translate_transform(x: f64, y: f64) -> String {
+    return format!("translate({}, {})", x, y);
+}
 
 pub fn translate_svg(svg_string: &str, x: f64, y: f64) -> String {
     format!(
-        "<g transform=\"translate({x}, {y})\">{content}</g>",
-        x = x,
-        y = y,
+        "<g transform=\"{transform}\">{content}</g>",
+        transform = translate_transform(x, y),
         content = svg_string
     )
 }
 
-pub fn rect_svg(rect: &Rect, fill: &str) -> String {
+pub fn rect_svg(rect: &Rect, fill: &Color) -> String {
     return format!(
         concat!(
             "<rect ",
@@ -17,6 +22,103 @@ pub fn rect_svg(rect: &Rect, fill: &str) -> String {
             "width=\"{}\" height=\"{}\" ",
             "fill=\"{}\"/>",
         ),
-        rect.x, rect.y, rect.width, rect.height, fill
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+        fill.to_css()
     );
-}
\ No newline at end of file
+}
+
+/// Path data tracing each polygon as a closed subpath
+pub fn polygons_path_data(polygons: &Vec<Vec<Vector2>>) -> String {
+    let subpaths: Vec<String> = polygons
+        .iter()
+        .filter(|polygon| !polygon.is_empty())
+        .map(|polygon| {
+            let points: Vec<String> = polygon
+                .iter()
+                .map(|point| format!("{} {}", point.x, point.y))
+                .collect();
+            return format!("M{}Z", points.join("L"));
+        })
+        .collect();
+    return subpaths.join("");
+}
+
+/// Svg for the filled part of a dot, centered on the origin
+pub fn shape_svg(geometry: &ShapeGeometry, fill: &Color) -> String {
+    return match geometry {
+        ShapeGeometry::Circle(radius) => {
+            format!("<circle r=\"{}\" fill=\"{}\" />", radius, fill.to_css())
+        }
+        ShapeGeometry::Polygons(polygons) => format!(
+            "<path d=\"{}\" fill=\"{}\" />",
+            polygons_path_data(polygons),
+            fill.to_css()
+        ),
+        ShapeGeometry::Empty => "".to_string(),
+    };
+}
+
+const TITLE_ID: &str = "radial-dots-title";
+const DESC_ID: &str = "radial-dots-desc";
+
+/// Escapes text for use in svg attribute values and text content
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    return escaped;
+}
+
+/// Wraps content in a group that assistive technology reads as a
+/// single image with the given label
+pub fn labelled_group_svg(label: &str, content: &str) -> String {
+    return format!(
+        "<g role=\"img\" aria-label=\"{label}\">{content}</g>",
+        label = escape_xml(label),
+        content = content
+    );
+}
+
+/// Wraps svg content in a root svg element whose viewBox is `view_box`,
+/// labelled by the given title and description. The svg's physical size
+/// is `millimeters_per_unit` millimeters per unit of the view box.
+pub fn svg_document(
+    view_box: &Rect,
+    millimeters_per_unit: f64,
+    title: &str,
+    description: &str,
+    content: &str,
+) -> String {
+    return format!(
+        concat!(
+            "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{} {} {} {}' ",
+            "width='{width}mm' height='{height}mm' ",
+            "role='img' aria-labelledby='{title_id} {desc_id}'>",
+            "<title id='{title_id}'>{title}</title>",
+            "<desc id='{desc_id}'>{description}</desc>",
+            "{content}</svg>"
+        ),
+        view_box.x,
+        view_box.y,
+        view_box.width,
+        view_box.height,
+        width = view_box.width * millimeters_per_unit,
+        height = view_box.height * millimeters_per_unit,
+        title_id = TITLE_ID,
+        desc_id = DESC_ID,
+        title = escape_xml(title),
+        description = escape_xml(description),
+        content = content
+    );
+}
@@ -0,0 +1,8 @@
+use crate::drawing_style::DrawingStyle;
+use virtual_dom_rs::VirtualNode;
+
+/// Counterpart to `SvgDrawable` that builds virtual dom nodes instead of
+/// markup, so the diagram can be diffed and patched element by element.
+pub trait VirtualSvgDrawable {
+    fn as_virtual_svg(&self, style: &DrawingStyle) -> VirtualNode;
+}
@@ -0,0 +1,121 @@
+use crate::color::Color;
+use crate::drawing_style::{ColorMode, DrawingStyle, StylePreset};
+use crate::fig::dot::Dot;
+use crate::fig::shape::DotShape;
+use crate::fig::style_override::{ArcOverride, DotOverride};
+use crate::fig::text_path::ArcStyle;
+
+pub const THEME_NAMES: [&str; 5] = ["light", "dark", "blueprint", "high-contrast", "print-ink"];
+
+fn circle_dot(circle_radius: f64, ring_stroke_width: f64) -> Dot {
+    return Dot {
+        shape: DotShape::Circle,
+        circle_radius: circle_radius,
+        ring_radius: 2.0,
+        ring_stroke_width: ring_stroke_width,
+        fill_color: None,
+        ring_color: None,
+        opacity: None,
+    };
+}
+
+fn full_arc(radius: f64) -> ArcStyle {
+    return ArcStyle {
+        radius: radius,
+        arc_percentage: 1.0,
+        arc_offset_percentage: 0.0,
+    };
+}
+
+/// A complete style by theme name, without any presets
+pub fn get_theme(name: &str) -> Option<DrawingStyle> {
+    let (stroke_color, background_color, zero_dot_style, one_dot_style) = match name {
+        "light" => (
+            Color::from_rgb(0x33, 0x33, 0x33),
+            Color::from_rgb(0xee, 0xee, 0xee),
+            circle_dot(1.0, 0.1),
+            circle_dot(0.5, 0.0),
+        ),
+        "dark" => (
+            Color::from_rgb(0xe8, 0xe8, 0xe8),
+            Color::from_rgb(0x1e, 0x1e, 0x24),
+            circle_dot(1.0, 0.1),
+            circle_dot(0.5, 0.0),
+        ),
+        "blueprint" => (
+            Color::from_rgb(0xf4, 0xf8, 0xff),
+            Color::from_rgb(0x1f, 0x4e, 0x8c),
+            circle_dot(0.0, 0.15),
+            circle_dot(0.6, 0.0),
+        ),
+        // pure black on white, with thicker rings that stay visible
+        // when printed small or viewed with low vision
+        "high-contrast" => (
+            Color::from_rgb(0x00, 0x00, 0x00),
+            Color::from_rgb(0xff, 0xff, 0xff),
+            circle_dot(1.0, 0.3),
+            circle_dot(0.7, 0.0),
+        ),
+        // a single ink on white paper, with zero dots left unfilled so
+        // large areas of ink don't bleed
+        "print-ink" => (
+            Color::from_rgb(0x1a, 0x1a, 0x1a),
+            Color::from_rgb(0xff, 0xff, 0xff),
+            circle_dot(0.0, 0.15),
+            circle_dot(0.6, 0.0),
+        ),
+        _ => return None,
+    };
+
+    return Some(DrawingStyle {
+        stroke_color: stroke_color,
+        background_color: background_color,
+        color_mode: ColorMode::Single,
+        default_zero_dot_style: zero_dot_style,
+        default_one_dot_style: one_dot_style,
+        default_arc_style: full_arc(5.0),
+        presets: vec![],
+    });
+}
+
+/// Presets every new document starts with. Each only sets the fields
+/// it is about, so they layer over any theme.
+pub fn built_in_presets() -> Vec<StylePreset> {
+    return vec![
+        StylePreset {
+            name: "outline".to_string(),
+            zero_dot_style: DotOverride {
+                circle_radius: Some(0.0),
+                ring_stroke_width: Some(0.2),
+                ..DotOverride::default()
+            },
+            one_dot_style: DotOverride {
+                circle_radius: Some(0.8),
+                ..DotOverride::default()
+            },
+            arc_style: ArcOverride::default(),
+        },
+        StylePreset {
+            name: "bold".to_string(),
+            zero_dot_style: DotOverride {
+                circle_radius: Some(1.2),
+                ring_stroke_width: Some(0.3),
+                ..DotOverride::default()
+            },
+            one_dot_style: DotOverride {
+                circle_radius: Some(0.9),
+                ..DotOverride::default()
+            },
+            arc_style: ArcOverride::default(),
+        },
+        StylePreset {
+            name: "half-arc".to_string(),
+            zero_dot_style: DotOverride::default(),
+            one_dot_style: DotOverride::default(),
+            arc_style: ArcOverride {
+                arc_percentage: Some(0.5),
+                ..ArcOverride::default()
+            },
+        },
+    ];
+}
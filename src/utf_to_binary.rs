@@ -1,14 +1,17 @@
+/// Number of bits each character of text is encoded as
+pub const BITS_PER_CHAR: usize = 8;
+
 pub fn text_to_binary(input: &str) -> Option<Vec<bool>> {
     let mut cur = [0];
-    let mut result: Vec<bool> = Vec::with_capacity(input.len() * 8);
+    let mut result: Vec<bool> = Vec::with_capacity(input.len() * BITS_PER_CHAR);
     for c in input.chars() {
         if !c.is_ascii() {
             return None;
         }
         c.encode_utf8(&mut cur);
 
-        for i in 0..8 {
-            result.push((1 << i) & i != 0);
+        for i in 0..BITS_PER_CHAR {
+            result.push((1 << i) & cur[0] != 0);
         }
     }
     return Some(result);